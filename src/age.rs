@@ -0,0 +1,170 @@
+//! Contains traditional age (数え年), yakudoshi (厄年) and lunar birthday calculations.
+
+use anyhow::{bail, Result};
+use chrono::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    calendar::{meridian_timezone, JST_OFFSET},
+    sekki::Sekki,
+    tempo::TempoDate,
+};
+
+/// Represents the day on which kazoedoshi is incremented.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum NewYear {
+    /// 1/1 in tempo calendar.
+    #[default]
+    Kyureki,
+
+    /// Risshun (立春) in JST.
+    Risshun,
+}
+
+impl NewYear {
+    /// Gets the year which the JST date belongs to.
+    pub fn year_of(self, date: NaiveDate) -> Result<i32> {
+        match self {
+            NewYear::Kyureki => {
+                let tempo_date = TempoDate::from_gregory_date(Utc.from_utc_date(&date))?;
+                Ok(tempo_date.year as i32)
+            }
+            NewYear::Risshun => {
+                let jst = meridian_timezone(JST_OFFSET);
+                let risshun = Sekki::Risshun.instant_in_year(date.year())?;
+                if date >= risshun.with_timezone(&jst).date().naive_local() {
+                    Ok(date.year())
+                } else {
+                    Ok(date.year() - 1)
+                }
+            }
+        }
+    }
+}
+
+/// Represents sex, which decides yakudoshi ages.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Sex {
+    Male,
+    Female,
+}
+
+impl Sex {
+    /// Gets kazoedoshi ages of honyaku (本厄).
+    pub fn honyaku_ages(self) -> &'static [usize] {
+        match self {
+            Sex::Male => &[25, 42, 61],
+            Sex::Female => &[19, 33, 37, 61],
+        }
+    }
+}
+
+/// Represents a year of yakudoshi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Yakudoshi {
+    Maeyaku,
+    Honyaku,
+    Atoyaku,
+}
+
+#[allow(dead_code)]
+impl Yakudoshi {
+    /// Gets Japanese string.
+    pub fn to_japanese(self) -> &'static str {
+        match self {
+            Yakudoshi::Maeyaku => "前厄",
+            Yakudoshi::Honyaku => "本厄",
+            Yakudoshi::Atoyaku => "後厄",
+        }
+    }
+
+    /// Gets Hepburn romanization.
+    pub fn to_romaji(self) -> &'static str {
+        match self {
+            Yakudoshi::Maeyaku => "Maeyaku",
+            Yakudoshi::Honyaku => "Hon'yaku",
+            Yakudoshi::Atoyaku => "Atoyaku",
+        }
+    }
+
+    /// Gets English translation.
+    pub fn to_english(self) -> &'static str {
+        match self {
+            Yakudoshi::Maeyaku => "year before the unlucky year",
+            Yakudoshi::Honyaku => "unlucky year",
+            Yakudoshi::Atoyaku => "year after the unlucky year",
+        }
+    }
+}
+
+/// Calculates kazoedoshi at the date, which is 1 at birth and incremented on every new year.
+pub fn kazoedoshi(birth_date: NaiveDate, date: NaiveDate, new_year: NewYear) -> Result<usize> {
+    if date < birth_date {
+        bail!("The date {} is before the birth date {}", date, birth_date);
+    }
+    let age = new_year.year_of(date)? - new_year.year_of(birth_date)? + 1;
+    Ok(age as usize)
+}
+
+/// Calculates kazoedoshi in the lunar year, counted from the lunar year of birth as 1.
+pub fn kazoedoshi_in_year(birth_date: NaiveDate, year: i32) -> Result<Option<usize>> {
+    let age = year - NewYear::Kyureki.year_of(birth_date)? + 1;
+    Ok(if age >= 1 { Some(age as usize) } else { None })
+}
+
+/// Determines whether the lunar year is yakudoshi of the person.
+pub fn yakudoshi(birth_date: NaiveDate, year: i32, sex: Sex) -> Result<Option<Yakudoshi>> {
+    let age = match kazoedoshi_in_year(birth_date, year)? {
+        Some(age) => age,
+        None => return Ok(None),
+    };
+
+    let yakudoshi = sex.honyaku_ages().iter().find_map(|&honyaku| match age {
+        a if a + 1 == honyaku => Some(Yakudoshi::Maeyaku),
+        a if a == honyaku => Some(Yakudoshi::Honyaku),
+        a if a == honyaku + 1 => Some(Yakudoshi::Atoyaku),
+        _ => None,
+    });
+    Ok(yakudoshi)
+}
+
+/// Calculates the lunar birthday in the lunar year, which has the same month and day of birth
+/// in tempo calendar.
+/// A leap month falls back to the normal month and the 30th day falls back to the 29th day
+/// if they do not exist in the year.
+pub fn lunar_birthday(birth_date: NaiveDate, year: usize) -> Result<NaiveDate> {
+    let birth = TempoDate::from_gregory_date(Utc.from_utc_date(&birth_date))?;
+    if year < birth.year {
+        bail!("The year {} is before the birth year {}", year, birth.year);
+    }
+
+    let leap_months: &[bool] = if birth.leap_month {
+        &[true, false]
+    } else {
+        &[false]
+    };
+    let days: &[usize] = if birth.day == 30 {
+        &[30, 29]
+    } else {
+        &[birth.day]
+    };
+    let mut last_error = None;
+    for &leap_month in leap_months {
+        for &day in days {
+            let tempo_date = TempoDate {
+                year,
+                leap_month,
+                month: birth.month,
+                day,
+                ..Default::default()
+            };
+            match tempo_date.to_gregory_date() {
+                Ok(date) => return Ok(date),
+                Err(e) => last_error = Some(e),
+            }
+        }
+    }
+    Err(last_error.expect("Should have tried at least once"))
+}
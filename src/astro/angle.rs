@@ -0,0 +1,62 @@
+//! Contains angle types and normalizations.
+//! Longitude models and coordinates calculate in plain `f64`,
+//! while solvers of sakus and sekkis take target longitudes and residuals as `Degree`.
+
+/// Represents an angle in degree.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Degree(pub f64);
+
+/// Represents an angle in radian.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Radian(pub f64);
+
+impl Degree {
+    /// Normalizes into [0, 360).
+    pub fn normalized(self) -> Degree {
+        Degree(normalize(self.0))
+    }
+
+    /// Calculates the difference from the other angle normalized into (-180, 180].
+    pub fn signed_from(self, from: Degree) -> Degree {
+        Degree(signed_difference(self.0, from.0))
+    }
+
+    /// Calculates how far this angle is ahead of the other, normalized into [0, 360).
+    pub fn ahead_of(self, from: Degree) -> Degree {
+        Degree(normalize(self.0 - from.0))
+    }
+}
+
+impl From<Degree> for Radian {
+    fn from(degree: Degree) -> Radian {
+        Radian(degree.0.to_radians())
+    }
+}
+
+impl From<Radian> for Degree {
+    fn from(radian: Radian) -> Degree {
+        Degree(radian.0.to_degrees())
+    }
+}
+
+/// Normalizes the angle into [0, 360) degree.
+pub fn normalize(degree: f64) -> f64 {
+    // `rem_euclid` rounds tiny negative values up to 360
+    let normalized = degree.rem_euclid(360.0);
+    if normalized < 360.0 {
+        normalized
+    } else {
+        0.0
+    }
+}
+
+/// Normalizes the angle into (-180, 180] degree.
+pub fn normalize_signed(degree: f64) -> f64 {
+    180.0 - normalize(180.0 - degree)
+}
+
+/// Calculates the difference `to - from` normalized into (-180, 180] degree.
+/// The result is positive when `to` is ahead of `from` by less than a half turn.
+pub fn signed_difference(to: f64, from: f64) -> f64 {
+    normalize_signed(to - from)
+}
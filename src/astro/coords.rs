@@ -0,0 +1,93 @@
+//! Contains coordinate transformations.
+
+use crate::astro::{
+    angle::{Degree, Radian},
+    julian::julian_century,
+    math::{asin, atan, atan2, cos, sin, tan},
+};
+
+/// Equatorial radius of the earth in kilometer.
+pub const EARTH_RADIUS: f64 = 6378.14;
+
+/// Ratio of the polar radius of the earth to the equatorial one.
+const EARTH_AXIS_RATIO: f64 = 0.99664719;
+
+/// Calculates the mean obliquity of the ecliptic in degree.
+pub fn obliquity(jd: f64) -> f64 {
+    23.439291 - 0.0130042 * julian_century(jd)
+}
+
+/// Calculates nutation in longitude in degree, by the principal terms.
+pub fn nutation_in_longitude(jd: f64) -> f64 {
+    let jc = julian_century(jd);
+    let omega = (125.04452 - 1934.136261 * jc).to_radians();
+    let l_sun = (280.4665 + 36000.7698 * jc).to_radians();
+    let l_moon = (218.3165 + 481267.8813 * jc).to_radians();
+    let arcsec = -17.2 * sin(omega) - 1.32 * sin(2.0 * l_sun) - 0.23 * sin(2.0 * l_moon)
+        + 0.21 * sin(2.0 * omega);
+    arcsec / 3600.0
+}
+
+/// Converts ecliptic longitude and latitude into right ascension and declination, all in degree.
+pub fn ecliptic_to_equatorial(jd: f64, longitude: f64, latitude: f64) -> (f64, f64) {
+    let epsilon = obliquity(jd).to_radians();
+    let (lambda, beta) = (longitude.to_radians(), latitude.to_radians());
+
+    let right_ascension = Radian(atan2(
+        sin(lambda) * cos(epsilon) - tan(beta) * sin(epsilon),
+        cos(lambda),
+    ));
+    let declination =
+        asin(sin(beta) * cos(epsilon) + cos(beta) * sin(epsilon) * sin(lambda)).to_degrees();
+    (Degree::from(right_ascension).normalized().0, declination)
+}
+
+/// Converts hour angle and declination into altitude and azimuth (from north to east), all in degree.
+pub fn equatorial_to_horizontal(hour_angle: f64, declination: f64, latitude: f64) -> (f64, f64) {
+    let (h, delta, phi) = (
+        hour_angle.to_radians(),
+        declination.to_radians(),
+        latitude.to_radians(),
+    );
+
+    let altitude = asin(sin(phi) * sin(delta) + cos(phi) * cos(delta) * cos(h));
+    let azimuth = Radian(atan2(
+        -cos(delta) * sin(h),
+        sin(delta) * cos(phi) - cos(delta) * sin(phi) * cos(h),
+    ));
+    (altitude.to_degrees(), Degree::from(azimuth).normalized().0)
+}
+
+/// Converts geocentric right ascension and declination into topocentric ones, all in degree,
+/// for the body at the distance in kilometer seen from the latitude and the local sidereal time.
+/// The observer is assumed to be at the sea level.
+pub fn geocentric_to_topocentric(
+    right_ascension: f64,
+    declination: f64,
+    distance: f64,
+    latitude: f64,
+    sidereal_time: f64,
+) -> (f64, f64) {
+    // Geocentric position of the observer on the spheroid, in the equatorial radius
+    let u = atan(EARTH_AXIS_RATIO * tan(latitude.to_radians()));
+    let (rho_sin, rho_cos) = (EARTH_AXIS_RATIO * sin(u), cos(u));
+
+    let sin_parallax = EARTH_RADIUS / distance;
+    let (h, delta) = (
+        (sidereal_time - right_ascension).to_radians(),
+        declination.to_radians(),
+    );
+    let denominator = cos(delta) - rho_cos * sin_parallax * cos(h);
+    let delta_alpha = atan2(-rho_cos * sin_parallax * sin(h), denominator);
+    let topocentric_declination = atan2(
+        (sin(delta) - rho_sin * sin_parallax) * cos(delta_alpha),
+        denominator,
+    );
+
+    let topocentric_right_ascension =
+        Degree(right_ascension + delta_alpha.to_degrees()).normalized();
+    (
+        topocentric_right_ascension.0,
+        topocentric_declination.to_degrees(),
+    )
+}
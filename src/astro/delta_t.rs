@@ -0,0 +1,70 @@
+//! Contains ΔT (TT - UT) estimation.
+
+/// Observed ΔT in seconds at the beginning of years.
+const DELTA_T_TABLE: &[(f64, f64)] = &[
+    (1600.0, 120.0),
+    (1650.0, 50.0),
+    (1700.0, 9.0),
+    (1750.0, 13.0),
+    (1800.0, 13.7),
+    (1850.0, 7.1),
+    (1900.0, -2.7),
+    (1910.0, 10.4),
+    (1920.0, 21.2),
+    (1930.0, 24.0),
+    (1940.0, 24.3),
+    (1950.0, 29.2),
+    (1960.0, 33.2),
+    (1970.0, 40.2),
+    (1980.0, 50.5),
+    (1990.0, 56.9),
+    (2000.0, 63.8),
+    (2010.0, 66.1),
+    (2020.0, 69.4),
+];
+
+/// Calculates ΔT in seconds at the decimal year.
+/// The table is interpolated linearly, and the polynomial by Espenak and Meeus is used before it.
+/// After the table, the long-term parabola by Morrison and Stephenson is shifted to connect.
+pub fn delta_t(year: f64) -> f64 {
+    let (first_year, first_value) = DELTA_T_TABLE[0];
+    let (last_year, last_value) = DELTA_T_TABLE[DELTA_T_TABLE.len() - 1];
+
+    if year < 500.0 {
+        long_term_parabola(year)
+    } else if year < first_year {
+        let u = (year - 1000.0) / 100.0;
+        let coefficients = [
+            1574.2,
+            -556.01,
+            71.23472,
+            0.319781,
+            -0.8503463,
+            -0.005050998,
+            0.0083572073,
+        ];
+        let value = coefficients.iter().rev().fold(0.0, |acc, c| acc * u + c);
+        // Connects to the table continuously
+        value + (first_value - 120.3) * (year - 500.0) / (first_year - 500.0)
+    } else if year < last_year {
+        let index = DELTA_T_TABLE
+            .windows(2)
+            .position(|w| (w[0].0..w[1].0).contains(&year))
+            .expect("Should be in the table");
+        let ((y0, v0), (y1, v1)) = (DELTA_T_TABLE[index], DELTA_T_TABLE[index + 1]);
+        v0 + (v1 - v0) * (year - y0) / (y1 - y0)
+    } else {
+        long_term_parabola(year) - long_term_parabola(last_year) + last_value
+    }
+}
+
+/// Calculates ΔT in seconds by the long-term parabola.
+fn long_term_parabola(year: f64) -> f64 {
+    let u = (year - 1820.0) / 100.0;
+    -20.0 + 32.0 * u * u
+}
+
+/// Calculates ΔT in seconds at the Julian Date.
+pub fn delta_t_at(jd: f64) -> f64 {
+    delta_t(2000.0 + (jd - 2451545.0) / 365.25)
+}
@@ -0,0 +1,410 @@
+//! Contains eclipse prediction.
+//! The method is taken from Meeus, "Astronomical Algorithms", Chapter 54.
+
+use crate::astro::{
+    angle::signed_difference,
+    julian::{scale::Tt, JulianDate},
+    longitude::jcg78::sun_longitude,
+    math::{cos, sin},
+    moon::Node,
+    solver::find_root,
+};
+
+/// Mean length of synodic month in days used by the lunation number.
+const MEAN_SYNODIC_MONTH: f64 = 29.530588861;
+
+/// Largest angular distance of the sun from a node where a solar eclipse can occur, in degree.
+/// Lunar eclipses require the sun to be closer, so this bounds both.
+const ECLIPSE_LIMIT: f64 = 18.5;
+
+/// Mean speed of the sun relative to the lunar node in degree per day.
+const SUN_NODE_SPEED: f64 = 360.0 / 346.62;
+
+/// Represents a type of solar eclipse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SolarEclipseType {
+    Partial,
+    Annular,
+    Total,
+    Hybrid,
+}
+
+impl SolarEclipseType {
+    /// Gets Japanese string.
+    pub fn to_japanese(self) -> &'static str {
+        match self {
+            SolarEclipseType::Partial => "部分日食",
+            SolarEclipseType::Annular => "金環日食",
+            SolarEclipseType::Total => "皆既日食",
+            SolarEclipseType::Hybrid => "金環皆既日食",
+        }
+    }
+
+    /// Gets English translation.
+    pub fn to_english(self) -> &'static str {
+        match self {
+            SolarEclipseType::Partial => "partial",
+            SolarEclipseType::Annular => "annular",
+            SolarEclipseType::Total => "total",
+            SolarEclipseType::Hybrid => "hybrid",
+        }
+    }
+}
+
+/// Represents a solar eclipse.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SolarEclipse {
+    /// Instant of the greatest eclipse in Julian Date (UTC).
+    pub jd: f64,
+
+    /// Type of the eclipse.
+    pub kind: SolarEclipseType,
+
+    /// Least distance from the axis of the moon's shadow to the center of the earth,
+    /// in equatorial radii of the earth.
+    pub gamma: f64,
+
+    /// Greatest magnitude.
+    /// For central eclipses, it is the ratio of the apparent diameters of the moon and the sun.
+    pub magnitude: f64,
+
+    /// Node near which the moon is.
+    pub node: Node,
+}
+
+/// Represents a type of lunar eclipse.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LunarEclipseType {
+    Penumbral,
+    Partial,
+    Total,
+}
+
+impl LunarEclipseType {
+    /// Gets Japanese string.
+    pub fn to_japanese(self) -> &'static str {
+        match self {
+            LunarEclipseType::Penumbral => "半影月食",
+            LunarEclipseType::Partial => "部分月食",
+            LunarEclipseType::Total => "皆既月食",
+        }
+    }
+
+    /// Gets English translation.
+    pub fn to_english(self) -> &'static str {
+        match self {
+            LunarEclipseType::Penumbral => "penumbral",
+            LunarEclipseType::Partial => "partial",
+            LunarEclipseType::Total => "total",
+        }
+    }
+}
+
+/// Represents a lunar eclipse.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LunarEclipse {
+    /// Instant of the greatest eclipse in Julian Date (UTC).
+    pub jd: f64,
+
+    /// Type of the eclipse.
+    pub kind: LunarEclipseType,
+
+    /// Least distance from the center of the moon to the axis of the earth's shadow,
+    /// in equatorial radii of the earth.
+    pub gamma: f64,
+
+    /// Magnitude in the penumbra.
+    pub penumbral_magnitude: f64,
+
+    /// Magnitude in the umbra, negative for penumbral eclipses.
+    pub umbral_magnitude: f64,
+
+    /// Beginning and end of the penumbral phase (P1 and P4).
+    pub penumbral: (f64, f64),
+
+    /// Beginning and end of the partial phase (U1 and U4).
+    pub partial: Option<(f64, f64)>,
+
+    /// Beginning and end of the total phase (U2 and U3).
+    pub total: Option<(f64, f64)>,
+
+    /// Node near which the moon is.
+    pub node: Node,
+}
+
+/// Represents an eclipse season, while the sun is close enough to a node for eclipses.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EclipseSeason {
+    /// Node which the sun passes.
+    pub node: Node,
+
+    /// Instant when the sun enters the eclipse limit, in Julian Date.
+    pub jd_start: f64,
+
+    /// Instant when the sun passes the node, in Julian Date.
+    pub jd_middle: f64,
+
+    /// Instant when the sun leaves the eclipse limit, in Julian Date.
+    pub jd_end: f64,
+}
+
+/// Elements of a syzygy at the lunation number.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct SyzygyElements {
+    /// Instant of the greatest eclipse in Julian Ephemeris Date.
+    jde: f64,
+
+    /// Least distance of the shadow axis from the center of the earth.
+    gamma: f64,
+
+    /// Radius of the umbral cone in the fundamental plane.
+    u: f64,
+
+    /// Mean anomaly of the moon in radian.
+    moon_anomaly: f64,
+
+    /// Node near which the moon is.
+    node: Node,
+}
+
+impl SyzygyElements {
+    /// Calculates the elements at the lunation number (integer for new moons, +0.5 for full moons).
+    /// Returns `None` when the moon is too far from the node for an eclipse.
+    fn at(k: f64) -> Option<SyzygyElements> {
+        let jc = k / 1236.85;
+        let (t2, t3, t4) = (jc * jc, jc * jc * jc, jc * jc * jc * jc);
+
+        let f = 160.7108 + 390.67050284 * k - 0.0016118 * t2 - 0.00000227 * t3 + 0.000000011 * t4;
+        if sin(f.to_radians()).abs() > 0.36 {
+            return None;
+        }
+
+        let jde_mean = 2451550.09766 + MEAN_SYNODIC_MONTH * k + 0.00015437 * t2 - 0.000000150 * t3
+            + 0.00000000073 * t4;
+        let m = (2.5534 + 29.10535670 * k - 0.0000014 * t2 - 0.00000011 * t3).to_radians();
+        let mp = (201.5643 + 385.81693528 * k + 0.0107582 * t2 + 0.00001238 * t3
+            - 0.000000058 * t4)
+            .to_radians();
+        let omega = (124.7746 - 1.56375588 * k + 0.0020672 * t2 + 0.00000215 * t3).to_radians();
+        let e = 1.0 - 0.002516 * jc - 0.0000074 * t2;
+        let f1 = f.to_radians() - 0.02665f64.to_radians() * sin(omega);
+        let a1 = (299.77 + 0.107408 * k - 0.009173 * t2).to_radians();
+
+        let (c_mp, c_m) = if k.fract() == 0.0 {
+            (-0.4075, 0.1721)
+        } else {
+            (-0.4065, 0.1727)
+        };
+        let correction = c_mp * sin(mp) + c_m * e * sin(m) + 0.0161 * sin(2.0 * mp)
+            - 0.0097 * sin(2.0 * f1)
+            + 0.0073 * e * sin(mp - m)
+            - 0.0050 * e * sin(mp + m)
+            - 0.0023 * sin(mp - 2.0 * f1)
+            + 0.0021 * e * sin(2.0 * m)
+            + 0.0012 * sin(mp + 2.0 * f1)
+            + 0.0006 * e * sin(2.0 * mp + m)
+            - 0.0004 * sin(3.0 * mp)
+            - 0.0003 * e * sin(m + 2.0 * f1)
+            + 0.0003 * sin(a1)
+            - 0.0002 * e * sin(m - 2.0 * f1)
+            - 0.0002 * e * sin(2.0 * mp - m)
+            - 0.0002 * sin(omega);
+
+        let p = 0.2070 * e * sin(m) + 0.0024 * e * sin(2.0 * m) - 0.0392 * sin(mp)
+            + 0.0116 * sin(2.0 * mp)
+            - 0.0073 * e * sin(mp + m)
+            + 0.0067 * e * sin(mp - m)
+            + 0.0118 * sin(2.0 * f1);
+        let q = 5.2207 - 0.0048 * e * cos(m) + 0.0020 * e * cos(2.0 * m)
+            - 0.3299 * cos(mp)
+            - 0.0060 * e * cos(mp + m)
+            + 0.0041 * e * cos(mp - m);
+        let w = cos(f1).abs();
+        let gamma = (p * cos(f1) + q * sin(f1)) * (1.0 - 0.0048 * w);
+        let u = 0.0059 + 0.0046 * e * cos(m) - 0.0182 * cos(mp) + 0.0004 * cos(2.0 * mp)
+            - 0.0005 * cos(m + mp);
+
+        Some(SyzygyElements {
+            jde: jde_mean + correction,
+            gamma,
+            u,
+            moon_anomaly: mp,
+            // The argument of latitude is measured from the ascending node
+            node: if cos(f.to_radians()) > 0.0 {
+                Node::Ascending
+            } else {
+                Node::Descending
+            },
+        })
+    }
+}
+
+/// Calculates the magnitude of a central eclipse on the shadow axis.
+/// The radii of the penumbral and umbral cones are taken at the surface of the earth,
+/// which is closer to the moon than the fundamental plane by `sqrt(1 - gamma^2)` .
+fn central_magnitude(gamma: f64, u: f64) -> f64 {
+    let z = (1.0 - gamma * gamma).max(0.0).sqrt();
+    let penumbral_radius = 0.5461 + u - 0.004687 * z;
+    let umbral_radius = u - 0.004664 * z;
+    (penumbral_radius - umbral_radius) / (penumbral_radius + umbral_radius)
+}
+
+/// Examines the solar eclipse at the new moon of the lunation number.
+fn solar_eclipse_at(k: f64) -> Option<SolarEclipse> {
+    let SyzygyElements {
+        jde,
+        gamma,
+        u,
+        node,
+        ..
+    } = SyzygyElements::at(k)?;
+    let abs_gamma = gamma.abs();
+    if abs_gamma > 1.5433 + u {
+        return None;
+    }
+
+    let (kind, magnitude) = if abs_gamma < 0.9972 + u.abs() {
+        // Central eclipses, and non-central ones whose umbra touches the earth
+        let kind = if u < 0.0 {
+            SolarEclipseType::Total
+        } else if u > 0.0047 || u >= 0.00464 * (1.0 - gamma * gamma).sqrt() {
+            SolarEclipseType::Annular
+        } else {
+            SolarEclipseType::Hybrid
+        };
+        (kind, central_magnitude(gamma, u))
+    } else {
+        let magnitude = (1.5433 + u - abs_gamma) / (0.5461 + 2.0 * u);
+        (SolarEclipseType::Partial, magnitude)
+    };
+
+    Some(SolarEclipse {
+        jd: JulianDate::<Tt>::new(jde).to_utc().value(),
+        kind,
+        gamma,
+        magnitude,
+        node,
+    })
+}
+
+/// Examines the lunar eclipse at the full moon of the lunation number.
+fn lunar_eclipse_at(k: f64) -> Option<LunarEclipse> {
+    let SyzygyElements {
+        jde,
+        gamma,
+        u,
+        moon_anomaly,
+        node,
+    } = SyzygyElements::at(k)?;
+    let abs_gamma = gamma.abs();
+    let penumbral_magnitude = (1.5573 + u - abs_gamma) / 0.5450;
+    if penumbral_magnitude <= 0.0 {
+        return None;
+    }
+    let umbral_magnitude = (1.0128 - u - abs_gamma) / 0.5450;
+
+    // Semidurations are calculated in the fundamental plane, and converted into days
+    let jd = JulianDate::<Tt>::new(jde).to_utc().value();
+    let n = 0.5458 + 0.0400 * cos(moon_anomaly);
+    let phase = |radius: f64| {
+        let semiduration = (radius * radius - gamma * gamma).sqrt() / n / 24.0;
+        (jd - semiduration, jd + semiduration)
+    };
+    let partial_radius = 1.0128 - u;
+    let total_radius = 0.4678 - u;
+
+    let kind = if abs_gamma < total_radius {
+        LunarEclipseType::Total
+    } else if abs_gamma < partial_radius {
+        LunarEclipseType::Partial
+    } else {
+        LunarEclipseType::Penumbral
+    };
+
+    Some(LunarEclipse {
+        jd,
+        kind,
+        gamma,
+        penumbral_magnitude,
+        umbral_magnitude,
+        penumbral: phase(1.5573 + u),
+        partial: Some(phase(partial_radius)).filter(|_| kind != LunarEclipseType::Penumbral),
+        total: Some(phase(total_radius)).filter(|_| kind == LunarEclipseType::Total),
+        node,
+    })
+}
+
+/// Calculates the range of lunation numbers covering the range.
+fn lunation_range(jd_from: f64, jd_to: f64) -> (i64, i64) {
+    let k_from = ((jd_from - 2451550.09766) / MEAN_SYNODIC_MONTH).floor() - 1.0;
+    let k_to = ((jd_to - 2451550.09766) / MEAN_SYNODIC_MONTH).ceil() + 1.0;
+    (k_from as i64, k_to as i64)
+}
+
+/// Searches lunar eclipses whose greatest instants are in the range.
+pub fn lunar_eclipses(jd_from: f64, jd_to: f64) -> Vec<LunarEclipse> {
+    let (k_from, k_to) = lunation_range(jd_from, jd_to);
+    (k_from..=k_to)
+        .filter_map(|k| lunar_eclipse_at(k as f64 + 0.5))
+        .filter(|e| (jd_from..jd_to).contains(&e.jd))
+        .collect()
+}
+
+/// Searches solar eclipses whose greatest instants are in the range.
+pub fn solar_eclipses(jd_from: f64, jd_to: f64) -> Vec<SolarEclipse> {
+    let (k_from, k_to) = lunation_range(jd_from, jd_to);
+    (k_from..=k_to)
+        .filter_map(|k| solar_eclipse_at(k as f64))
+        .filter(|e| (jd_from..jd_to).contains(&e.jd))
+        .collect()
+}
+
+/// Searches eclipse seasons whose middles are in the range.
+/// The sun is compared with the true node, which oscillates from the mean node by 1.5 degree.
+pub fn eclipse_seasons(jd_from: f64, jd_to: f64) -> Vec<EclipseSeason> {
+    // The sun gains 10 degree on a node in 10 days, so the scan never skips crossings
+    let step = 10.0;
+    let mut seasons = vec![];
+    for &node in [Node::Ascending, Node::Descending].iter() {
+        let distance = |jd: f64| signed_difference(sun_longitude(jd), node.longitude(jd));
+        let mut jd = jd_from;
+        let mut last = distance(jd);
+        while jd < jd_to {
+            let jd_next = (jd + step).min(jd_to);
+            let next = distance(jd_next);
+            // Excludes jumps at the opposite node
+            if last < 0.0 && next >= 0.0 && next - last < 90.0 {
+                if let Some(season) = eclipse_season_around(node, jd, jd_next) {
+                    seasons.push(season);
+                }
+            }
+            jd = jd_next;
+            last = next;
+        }
+    }
+    seasons.sort_by(|a, b| a.jd_middle.total_cmp(&b.jd_middle));
+    seasons
+}
+
+/// Solves the eclipse season whose middle is in the bracket.
+fn eclipse_season_around(node: Node, jd_low: f64, jd_high: f64) -> Option<EclipseSeason> {
+    let distance = |jd: f64| signed_difference(sun_longitude(jd), node.longitude(jd));
+    let jd_middle = find_root(jd_low, jd_high, distance).ok()?;
+
+    // The relative speed varies by 3.5% at most, so margins of 10% bracket the limits
+    let half_span = ECLIPSE_LIMIT / SUN_NODE_SPEED;
+    let jd_start = find_root(jd_middle - half_span * 1.1, jd_middle, |jd| {
+        distance(jd) + ECLIPSE_LIMIT
+    })
+    .ok()?;
+    let jd_end = find_root(jd_middle, jd_middle + half_span * 1.1, |jd| {
+        distance(jd) - ECLIPSE_LIMIT
+    })
+    .ok()?;
+    Some(EclipseSeason {
+        node,
+        jd_start,
+        jd_middle,
+        jd_end,
+    })
+}
@@ -0,0 +1,36 @@
+//! Contains solvers of astronomical events.
+
+use anyhow::Result;
+
+use crate::{
+    astro::{
+        angle::Degree,
+        longitude::{jcg78::Jcg78, LongitudeModel},
+        solver::find_root_within,
+    },
+    budget,
+    error::ConversionError,
+};
+
+/// Mean length of tropical year in days.
+const TROPICAL_YEAR: f64 = 365.2422;
+
+/// Calculates the instant nearest to the Julian Date when the sun reaches the longitude.
+pub fn sun_reaches(longitude: Degree, jd_near: f64) -> Result<f64> {
+    sun_reaches_with(longitude, jd_near, &Jcg78)
+}
+
+/// Calculates the instant nearest to the Julian Date when the sun reaches the longitude in the model.
+pub fn sun_reaches_with<M: LongitudeModel + ?Sized>(
+    longitude: Degree,
+    jd_near: f64,
+    model: &M,
+) -> Result<f64> {
+    budget::check()?;
+    let difference = |jd: f64| Degree(model.sun_longitude(jd)).signed_from(longitude).0;
+
+    // The speed of the sun differs from the mean by 3.4% at most, so the error is within 7 days
+    let jd_mean = jd_near - difference(jd_near) * TROPICAL_YEAR / 360.0;
+    find_root_within(jd_mean - 8.0, jd_mean + 8.0, model.tolerance(), difference)
+        .map_err(|_| ConversionError::SolverDidNotConverge { jd: jd_mean }.into())
+}
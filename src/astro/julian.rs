@@ -1,52 +1,226 @@
 //! Contains Julian day/century manipulations.
 
-use chrono::prelude::*;
+use anyhow::Result;
+use chrono::{
+    naive::{MAX_DATE, MIN_DATE},
+    prelude::*,
+};
+
+use std::{convert::TryFrom, marker::PhantomData};
+
+use crate::{
+    astro::{
+        angle::normalize,
+        coords::{nutation_in_longitude, obliquity},
+        delta_t::delta_t_at,
+        leap_second::{tai_minus_utc, tai_minus_utc_in_tai},
+        math::cos,
+    },
+    error::ConversionError,
+};
+
+/// Julian Date of the epoch of Modified Julian Date.
+pub const MJD_EPOCH: f64 = 2400000.5;
+
+/// Julian Date of the midnight starting 0000-12-31, the day before 0001-01-01 in Gregorian calendar.
+const JD_BEFORE_CE: f64 = 1721424.5;
+
+/// TT - TAI in seconds.
+pub const TT_MINUS_TAI: f64 = 32.184;
+
+/// Nanoseconds in a day.
+const NANOSECONDS_PER_DAY: f64 = 86400.0e9;
+
+/// Contains marker types of time scales.
+pub mod scale {
+    /// Represents a time scale of Julian Date.
+    pub trait TimeScale {}
+
+    /// Coordinated Universal Time, which civil datetimes are in.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Utc {}
+
+    /// Universal Time defined by the rotation of the earth.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Ut1 {}
+
+    /// International Atomic Time, which UTC follows with leap seconds.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Tai {}
+
+    /// Terrestrial Time, which ephemerides are in.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq)]
+    pub enum Tt {}
+
+    impl TimeScale for Utc {}
+    impl TimeScale for Ut1 {}
+    impl TimeScale for Tai {}
+    impl TimeScale for Tt {}
+}
+
+/// Represents Julian Date in the time scale.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct JulianDate<S: scale::TimeScale> {
+    value: f64,
+    scale: PhantomData<S>,
+}
+
+impl<S: scale::TimeScale> JulianDate<S> {
+    /// Wraps the raw Julian Date.
+    pub fn new(value: f64) -> JulianDate<S> {
+        JulianDate {
+            value,
+            scale: PhantomData,
+        }
+    }
+
+    /// Gets the raw Julian Date.
+    pub fn value(self) -> f64 {
+        self.value
+    }
+
+    /// Gets Modified Julian Date.
+    pub fn modified(self) -> f64 {
+        to_modified_julian_date(self.value)
+    }
+}
+
+impl JulianDate<scale::Utc> {
+    /// Converts from the datetime.
+    pub fn from_datetime<Tz: TimeZone>(datetime: &DateTime<Tz>) -> JulianDate<scale::Utc> {
+        JulianDate::new(to_julian_date(datetime))
+    }
+
+    /// Converts into UT1, ignoring DUT1 which is less than a second.
+    pub fn to_ut1(self) -> JulianDate<scale::Ut1> {
+        JulianDate::new(self.value)
+    }
+
+    /// Converts into TAI by the leap seconds.
+    /// Before 1972, it is converted through UT1 and ΔT.
+    pub fn to_tai(self) -> JulianDate<scale::Tai> {
+        match tai_minus_utc(self.modified()) {
+            Some(seconds) => JulianDate::new(self.value + seconds / 86400.0),
+            None => self.to_ut1().to_tt().to_tai(),
+        }
+    }
+
+    /// Converts into TT through TAI.
+    pub fn to_tt(self) -> JulianDate<scale::Tt> {
+        self.to_tai().to_tt()
+    }
+}
+
+impl JulianDate<scale::Tai> {
+    /// Converts into UTC by the leap seconds.
+    /// Before 1972, it is converted through TT and ΔT.
+    pub fn to_utc(self) -> JulianDate<scale::Utc> {
+        match tai_minus_utc_in_tai(self.modified()) {
+            Some(seconds) => JulianDate::new(self.value - seconds / 86400.0),
+            None => JulianDate::new(self.to_tt().to_ut1().value),
+        }
+    }
+
+    /// Converts into TT by the constant offset.
+    pub fn to_tt(self) -> JulianDate<scale::Tt> {
+        JulianDate::new(self.value + TT_MINUS_TAI / 86400.0)
+    }
+}
+
+impl JulianDate<scale::Ut1> {
+    /// Converts into TT by ΔT.
+    pub fn to_tt(self) -> JulianDate<scale::Tt> {
+        JulianDate::new(self.value + delta_t_at(self.value) / 86400.0)
+    }
+}
+
+impl JulianDate<scale::Tt> {
+    /// Converts into UT1 by ΔT.
+    pub fn to_ut1(self) -> JulianDate<scale::Ut1> {
+        JulianDate::new(self.value - delta_t_at(self.value) / 86400.0)
+    }
+
+    /// Converts into TAI by the constant offset.
+    pub fn to_tai(self) -> JulianDate<scale::Tai> {
+        JulianDate::new(self.value - TT_MINUS_TAI / 86400.0)
+    }
+
+    /// Converts into UTC through TAI.
+    pub fn to_utc(self) -> JulianDate<scale::Utc> {
+        self.to_tai().to_utc()
+    }
+}
+
+/// Converts Julian Date into Modified Julian Date (MJD).
+pub fn to_modified_julian_date(jd: f64) -> f64 {
+    jd - MJD_EPOCH
+}
 
 /// Converts Gregory datetime into julian date (JD).
+/// The day part is counted exactly and only the time of day is fractional.
 pub fn to_julian_date<Tz: TimeZone>(datetime: &DateTime<Tz>) -> f64 {
     let datetime = datetime.naive_utc();
+    let days = datetime.num_days_from_ce() as f64;
 
-    let (y, m) = if datetime.month() <= 2 {
-        (datetime.year() as f64 - 1.0, datetime.month() as f64 + 12.0)
-    } else {
-        (datetime.year() as f64, datetime.month() as f64)
+    // Sub-second part is kept in nanoseconds
+    let seconds = datetime.num_seconds_from_midnight() as f64 + datetime.nanosecond() as f64 / 1e9;
+    JD_BEFORE_CE + days + seconds / 86400.0
+}
+
+/// Converts Julian date (JD) into Gregory datetime, rounded to nanoseconds.
+/// Converting the result back by `to_julian_date` reproduces the input within the precision of `f64`,
+/// which is about 40 microseconds for present dates.
+pub fn from_julian_date(jd: f64) -> Result<DateTime<Utc>> {
+    let out_of_range = || ConversionError::OutOfSupportedRange {
+        requested: format!("JD {}", jd),
+        min: MIN_DATE,
+        max: MAX_DATE,
     };
 
-    let mjd = (y * 365.25).floor() + (y / 400.0).floor() - (y / 100.0).floor()
-        + ((m - 2.0) * 30.59).floor()
-        + datetime.day() as f64
-        - 678912.0;
+    let elapsed = jd - JD_BEFORE_CE;
+    if !elapsed.is_finite() {
+        return Err(out_of_range().into());
+    }
 
-    let time = (datetime.hour() as f64 / 24.0)
-        + (datetime.minute() as f64 / 1440.0)
-        + (datetime.second() as f64 / 86400.0);
-    let jd = mjd + 2400000.5 + time;
+    // The time of day may be rounded up to the next midnight
+    let days = elapsed.floor();
+    let nanoseconds = ((elapsed - days) * NANOSECONDS_PER_DAY).round() as i64;
+    let (days, nanoseconds) = if nanoseconds >= NANOSECONDS_PER_DAY as i64 {
+        (days as i64 + 1, 0)
+    } else {
+        (days as i64, nanoseconds)
+    };
 
-    jd
+    let date = i32::try_from(days)
+        .ok()
+        .and_then(NaiveDate::from_num_days_from_ce_opt)
+        .ok_or_else(out_of_range)?;
+    let time = NaiveTime::from_num_seconds_from_midnight(
+        (nanoseconds / 1_000_000_000) as u32,
+        (nanoseconds % 1_000_000_000) as u32,
+    );
+    Ok(DateTime::from_utc(date.and_time(time), Utc))
 }
 
-/// Converts Julian date (JD) into Gregory datetime.
-pub fn from_julian_date(jd: f64) -> DateTime<Utc> {
-    let mjd = jd - 2400000.5;
-    let n = (mjd + 678881.0) as i32;
-    let a = n * 4 + 3 + ((((n + 1) * 4 / 146097) + 1) * 3 / 4) * 4;
-    let b = (a.rem_euclid(1461) / 4) * 5 + 2;
-
-    let mut year = a / 1461;
-    let mut month = b / 153 + 3;
-    let day = b.rem_euclid(153) / 5 + 1;
-
-    if month > 12 {
-        year += 1;
-        month -= 12;
-    }
+/// Gets Julian Date of the UTC midnight starting the date, without going through datetimes.
+pub fn julian_day_of(date: NaiveDate) -> f64 {
+    JD_BEFORE_CE + date.num_days_from_ce() as f64
+}
 
-    let time = mjd.fract();
-    let hour = (time * 24.0) as u32;
-    let minute = (time * 1440.0) as u32 % 60;
-    let second = (time * 86400.0) as u32 % 60;
-    Utc.ymd(year, month as u32, day as u32)
-        .and_hms(hour, minute, second)
+/// Gets the UTC date containing the Julian Date, truncating the time of day.
+pub fn date_of_julian_day(jd: f64) -> Result<NaiveDate> {
+    let days = (jd - JD_BEFORE_CE).floor();
+    (days.is_finite() && days.abs() < i32::MAX as f64)
+        .then(|| NaiveDate::from_num_days_from_ce_opt(days as i32))
+        .flatten()
+        .ok_or_else(|| {
+            ConversionError::OutOfSupportedRange {
+                requested: format!("JD {}", jd),
+                min: MIN_DATE,
+                max: MAX_DATE,
+            }
+            .into()
+        })
 }
 
 /// Calculates Julian century from J2000.0.
@@ -54,3 +228,25 @@ pub fn julian_century(jd: f64) -> f64 {
     // JD2451545 is 2000/01/01 12:00:00
     (jd - 2451545.0) / 36525.0
 }
+
+/// Calculates Greenwich mean sidereal time in degree.
+pub fn greenwich_mean_sidereal_time(jd: f64) -> f64 {
+    let jc = julian_century(jd);
+    normalize(280.46061837 + 360.98564736629 * (jd - 2451545.0) + 0.000387933 * jc * jc)
+}
+
+/// Calculates Greenwich apparent sidereal time in degree, corrected by the equation of the equinoxes.
+pub fn greenwich_apparent_sidereal_time(jd: f64) -> f64 {
+    let equation_of_equinoxes = nutation_in_longitude(jd) * cos(obliquity(jd).to_radians());
+    normalize(greenwich_mean_sidereal_time(jd) + equation_of_equinoxes)
+}
+
+/// Calculates local mean sidereal time in degree at the longitude (positive for east).
+pub fn local_mean_sidereal_time(jd: f64, longitude: f64) -> f64 {
+    normalize(greenwich_mean_sidereal_time(jd) + longitude)
+}
+
+/// Calculates local apparent sidereal time in degree at the longitude (positive for east).
+pub fn local_apparent_sidereal_time(jd: f64, longitude: f64) -> f64 {
+    normalize(greenwich_apparent_sidereal_time(jd) + longitude)
+}
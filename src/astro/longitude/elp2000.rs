@@ -0,0 +1,301 @@
+//! Moon longitude and latitude calculation by truncated ELP-2000/82 series.
+//! Terms are taken from Meeus, "Astronomical Algorithms", Chapter 47.
+
+use crate::astro::{
+    angle::normalize,
+    coords::nutation_in_longitude,
+    julian::{julian_century, scale::Ut1, JulianDate},
+    math::{cos, powi, sin},
+};
+
+/// Constants tuple for a periodic term.
+/// Each term has form of `A * sin(d * D + m * M + m' * M' + f * F)` in 1e-6 degree,
+/// where `A` is multiplied by `E^|m|`.
+type PeriodicTerm = (f64, f64, f64, f64, f64);
+
+/// Longitude terms as `(d, m, m', f, A)`.
+const LONGITUDE_TERMS: &[PeriodicTerm] = &[
+    (0.0, 0.0, 1.0, 0.0, 6288774.0),
+    (2.0, 0.0, -1.0, 0.0, 1274027.0),
+    (2.0, 0.0, 0.0, 0.0, 658314.0),
+    (0.0, 0.0, 2.0, 0.0, 213618.0),
+    (0.0, 1.0, 0.0, 0.0, -185116.0),
+    (0.0, 0.0, 0.0, 2.0, -114332.0),
+    (2.0, 0.0, -2.0, 0.0, 58793.0),
+    (2.0, -1.0, -1.0, 0.0, 57066.0),
+    (2.0, 0.0, 1.0, 0.0, 53322.0),
+    (2.0, -1.0, 0.0, 0.0, 45758.0),
+    (0.0, 1.0, -1.0, 0.0, -40923.0),
+    (1.0, 0.0, 0.0, 0.0, -34720.0),
+    (0.0, 1.0, 1.0, 0.0, -30383.0),
+    (2.0, 0.0, 0.0, -2.0, 15327.0),
+    (0.0, 0.0, 1.0, 2.0, -12528.0),
+    (0.0, 0.0, 1.0, -2.0, 10980.0),
+    (4.0, 0.0, -1.0, 0.0, 10675.0),
+    (0.0, 0.0, 3.0, 0.0, 10034.0),
+    (4.0, 0.0, -2.0, 0.0, 8548.0),
+    (2.0, 1.0, -1.0, 0.0, -7888.0),
+    (2.0, 1.0, 0.0, 0.0, -6766.0),
+    (1.0, 0.0, -1.0, 0.0, -5163.0),
+    (1.0, 1.0, 0.0, 0.0, 4987.0),
+    (2.0, -1.0, 1.0, 0.0, 4036.0),
+    (2.0, 0.0, 2.0, 0.0, 3994.0),
+    (4.0, 0.0, 0.0, 0.0, 3861.0),
+    (2.0, 0.0, -3.0, 0.0, 3665.0),
+    (0.0, 1.0, -2.0, 0.0, -2689.0),
+    (2.0, 0.0, -1.0, 2.0, -2602.0),
+    (2.0, -1.0, -2.0, 0.0, 2390.0),
+    (1.0, 0.0, 1.0, 0.0, -2348.0),
+    (2.0, -2.0, 0.0, 0.0, 2236.0),
+    (0.0, 1.0, 2.0, 0.0, -2120.0),
+    (0.0, 2.0, 0.0, 0.0, -2069.0),
+    (2.0, -2.0, -1.0, 0.0, 2048.0),
+    (2.0, 0.0, 1.0, -2.0, -1773.0),
+    (2.0, 0.0, 0.0, 2.0, -1595.0),
+    (4.0, -1.0, -1.0, 0.0, 1215.0),
+    (0.0, 0.0, 2.0, 2.0, -1110.0),
+    (3.0, 0.0, -1.0, 0.0, -892.0),
+    (2.0, 1.0, 1.0, 0.0, -810.0),
+    (4.0, -1.0, -2.0, 0.0, 759.0),
+    (0.0, 2.0, -1.0, 0.0, -713.0),
+    (2.0, 2.0, -1.0, 0.0, -700.0),
+    (2.0, 1.0, -2.0, 0.0, 691.0),
+    (2.0, -1.0, 0.0, -2.0, 596.0),
+    (4.0, 0.0, 1.0, 0.0, 549.0),
+    (0.0, 0.0, 4.0, 0.0, 537.0),
+    (4.0, -1.0, 0.0, 0.0, 520.0),
+    (1.0, 0.0, -2.0, 0.0, -487.0),
+    (2.0, 1.0, 0.0, -2.0, -399.0),
+    (0.0, 0.0, 2.0, -2.0, -381.0),
+    (1.0, 1.0, 1.0, 0.0, 351.0),
+    (3.0, 0.0, -2.0, 0.0, -340.0),
+    (4.0, 0.0, -3.0, 0.0, 330.0),
+    (2.0, -1.0, 2.0, 0.0, 327.0),
+    (0.0, 2.0, 1.0, 0.0, -323.0),
+    (1.0, 1.0, -1.0, 0.0, 299.0),
+    (2.0, 0.0, 3.0, 0.0, 294.0),
+];
+
+/// Latitude terms as `(d, m, m', f, A)`.
+const LATITUDE_TERMS: &[PeriodicTerm] = &[
+    (0.0, 0.0, 0.0, 1.0, 5128122.0),
+    (0.0, 0.0, 1.0, 1.0, 280602.0),
+    (0.0, 0.0, 1.0, -1.0, 277693.0),
+    (2.0, 0.0, 0.0, -1.0, 173237.0),
+    (2.0, 0.0, -1.0, 1.0, 55413.0),
+    (2.0, 0.0, -1.0, -1.0, 46271.0),
+    (2.0, 0.0, 0.0, 1.0, 32573.0),
+    (0.0, 0.0, 2.0, 1.0, 17198.0),
+    (2.0, 0.0, 1.0, -1.0, 9266.0),
+    (0.0, 0.0, 2.0, -1.0, 8822.0),
+    (2.0, -1.0, 0.0, -1.0, 8216.0),
+    (2.0, 0.0, -2.0, -1.0, 4324.0),
+    (2.0, 0.0, 1.0, 1.0, 4200.0),
+    (2.0, 1.0, 0.0, -1.0, -3359.0),
+    (2.0, -1.0, -1.0, 1.0, 2463.0),
+    (2.0, -1.0, 0.0, 1.0, 2211.0),
+    (2.0, -1.0, -1.0, -1.0, 2065.0),
+    (0.0, 1.0, -1.0, -1.0, -1870.0),
+    (4.0, 0.0, -1.0, -1.0, 1828.0),
+    (0.0, 1.0, 0.0, 1.0, -1794.0),
+    (0.0, 0.0, 0.0, 3.0, -1749.0),
+    (0.0, 1.0, -1.0, 1.0, -1565.0),
+    (1.0, 0.0, 0.0, 1.0, -1491.0),
+    (0.0, 1.0, 1.0, 1.0, -1475.0),
+    (0.0, 1.0, 1.0, -1.0, -1410.0),
+    (0.0, 1.0, 0.0, -1.0, -1344.0),
+    (1.0, 0.0, 0.0, -1.0, -1335.0),
+    (0.0, 0.0, 3.0, 1.0, 1107.0),
+    (4.0, 0.0, 0.0, -1.0, 1021.0),
+    (4.0, 0.0, -1.0, 1.0, 833.0),
+];
+
+/// Distance terms as `(d, m, m', f, A)`, where `A` is in meter and multiplies the cosine.
+const DISTANCE_TERMS: &[PeriodicTerm] = &[
+    (0.0, 0.0, 1.0, 0.0, -20905355.0),
+    (2.0, 0.0, -1.0, 0.0, -3699111.0),
+    (2.0, 0.0, 0.0, 0.0, -2955968.0),
+    (0.0, 0.0, 2.0, 0.0, -569925.0),
+    (0.0, 1.0, 0.0, 0.0, 48888.0),
+    (0.0, 0.0, 0.0, 2.0, -3149.0),
+    (2.0, 0.0, -2.0, 0.0, 246158.0),
+    (2.0, -1.0, -1.0, 0.0, -152138.0),
+    (2.0, 0.0, 1.0, 0.0, -170733.0),
+    (2.0, -1.0, 0.0, 0.0, -204586.0),
+    (0.0, 1.0, -1.0, 0.0, -129620.0),
+    (1.0, 0.0, 0.0, 0.0, 108743.0),
+    (0.0, 1.0, 1.0, 0.0, 104755.0),
+    (2.0, 0.0, 0.0, -2.0, 10321.0),
+    (0.0, 0.0, 1.0, -2.0, 79661.0),
+    (4.0, 0.0, -1.0, 0.0, -34782.0),
+    (0.0, 0.0, 3.0, 0.0, -23210.0),
+    (4.0, 0.0, -2.0, 0.0, -21636.0),
+    (2.0, 1.0, -1.0, 0.0, 24208.0),
+    (2.0, 1.0, 0.0, 0.0, 30824.0),
+    (1.0, 0.0, -1.0, 0.0, -8379.0),
+    (1.0, 1.0, 0.0, 0.0, -16675.0),
+    (2.0, -1.0, 1.0, 0.0, -12831.0),
+    (2.0, 0.0, 2.0, 0.0, -10445.0),
+    (4.0, 0.0, 0.0, 0.0, -11650.0),
+    (2.0, 0.0, -3.0, 0.0, 14403.0),
+    (0.0, 1.0, -2.0, 0.0, -7003.0),
+    (2.0, -1.0, -2.0, 0.0, 10056.0),
+    (1.0, 0.0, 1.0, 0.0, 6322.0),
+    (2.0, -2.0, 0.0, 0.0, -9884.0),
+    (0.0, 1.0, 2.0, 0.0, 5751.0),
+    (2.0, -2.0, -1.0, 0.0, -4950.0),
+    (2.0, 0.0, 1.0, -2.0, 4130.0),
+    (4.0, -1.0, -1.0, 0.0, -3958.0),
+    (3.0, 0.0, -1.0, 0.0, 3258.0),
+    (2.0, 1.0, 1.0, 0.0, 2616.0),
+    (4.0, -1.0, -2.0, 0.0, -1897.0),
+    (0.0, 2.0, -1.0, 0.0, -2117.0),
+    (2.0, 2.0, -1.0, 0.0, 2354.0),
+    (4.0, 0.0, 1.0, 0.0, -1423.0),
+    (0.0, 0.0, 4.0, 0.0, -1117.0),
+    (4.0, -1.0, 0.0, 0.0, -1571.0),
+    (1.0, 0.0, -2.0, 0.0, -1739.0),
+    (0.0, 0.0, 2.0, -2.0, -4421.0),
+    (0.0, 2.0, 1.0, 0.0, 1165.0),
+    (2.0, 0.0, -1.0, -2.0, 8752.0),
+];
+
+/// Mean distance of the moon in kilometer.
+const MEAN_DISTANCE: f64 = 385000.56;
+
+/// Represents fundamental arguments in degree.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FundamentalArguments {
+    /// Mean longitude of the moon (L').
+    pub mean_longitude: f64,
+
+    /// Mean elongation of the moon (D).
+    pub elongation: f64,
+
+    /// Mean anomaly of the sun (M).
+    pub sun_anomaly: f64,
+
+    /// Mean anomaly of the moon (M').
+    pub moon_anomaly: f64,
+
+    /// Argument of latitude of the moon (F).
+    pub latitude_argument: f64,
+
+    /// Eccentricity correction of the earth orbit (E).
+    pub eccentricity: f64,
+}
+
+impl FundamentalArguments {
+    /// Calculates the arguments at the Julian century.
+    pub fn at(jc: f64) -> FundamentalArguments {
+        let (t2, t3, t4) = (jc * jc, jc * jc * jc, jc * jc * jc * jc);
+        FundamentalArguments {
+            mean_longitude: 218.3164477 + 481267.88123421 * jc - 0.0015786 * t2 + t3 / 538841.0
+                - t4 / 65194000.0,
+            elongation: 297.8501921 + 445267.1114034 * jc - 0.0018819 * t2 + t3 / 545868.0
+                - t4 / 113065000.0,
+            sun_anomaly: 357.5291092 + 35999.0502909 * jc - 0.0001536 * t2 + t3 / 24490000.0,
+            moon_anomaly: 134.9633964 + 477198.8675055 * jc + 0.0087414 * t2 + t3 / 69699.0
+                - t4 / 14712000.0,
+            latitude_argument: 93.272095 + 483202.0175233 * jc - 0.0036539 * t2 - t3 / 3526000.0
+                + t4 / 863310000.0,
+            eccentricity: 1.0 - 0.002516 * jc - 0.0000074 * t2,
+        }
+    }
+
+    /// Evaluates the periodic sine terms in degree.
+    pub fn evaluate(&self, terms: &[PeriodicTerm]) -> f64 {
+        self.sum_terms(terms, sin) / 1e6
+    }
+
+    /// Sums the periodic terms with the trigonometric function.
+    fn sum_terms(&self, terms: &[PeriodicTerm], trigonometric: fn(f64) -> f64) -> f64 {
+        terms
+            .iter()
+            .map(|&(d, m, mp, f, a)| {
+                let argument = d * self.elongation
+                    + m * self.sun_anomaly
+                    + mp * self.moon_anomaly
+                    + f * self.latitude_argument;
+                a * powi(self.eccentricity, m.abs() as i32) * trigonometric(argument.to_radians())
+            })
+            .sum()
+    }
+}
+
+/// Calculates the apparent moon longitude.
+/// The argument is in UT and converted into TT for the series.
+/// The returned value will be in degree.
+pub fn moon_longitude(jd: f64) -> f64 {
+    let jd = JulianDate::<Ut1>::new(jd).to_tt().value();
+    let jc = julian_century(jd);
+    let args = FundamentalArguments::at(jc);
+
+    // Actions of Venus, Jupiter and the flattening of the earth
+    let a1 = (119.75 + 131.849 * jc).to_radians();
+    let a2 = (53.09 + 479264.29 * jc).to_radians();
+    let additive = (3958.0 * sin(a1)
+        + 1962.0 * sin((args.mean_longitude - args.latitude_argument).to_radians())
+        + 318.0 * sin(a2))
+        / 1e6;
+
+    let longitude = args.mean_longitude + args.evaluate(LONGITUDE_TERMS) + additive;
+    normalize(longitude + nutation_in_longitude(jd))
+}
+
+/// Calculates the moon latitude.
+/// The argument is in UT and converted into TT for the series.
+/// The returned value will be in degree, positive for north of the ecliptic.
+pub fn moon_latitude(jd: f64) -> f64 {
+    let jd = JulianDate::<Ut1>::new(jd).to_tt().value();
+    let jc = julian_century(jd);
+    let args = FundamentalArguments::at(jc);
+
+    // Actions of Venus and the flattening of the earth
+    let (l, mp, f) = (
+        args.mean_longitude.to_radians(),
+        args.moon_anomaly.to_radians(),
+        args.latitude_argument.to_radians(),
+    );
+    let a1 = (119.75 + 131.849 * jc).to_radians();
+    let a3 = (313.45 + 481266.484 * jc).to_radians();
+    let additive = (-2235.0 * sin(l)
+        + 382.0 * sin(a3)
+        + 175.0 * sin(a1 - f)
+        + 175.0 * sin(a1 + f)
+        + 127.0 * sin(l - mp)
+        - 115.0 * sin(l + mp))
+        / 1e6;
+
+    args.evaluate(LATITUDE_TERMS) + additive
+}
+
+/// Calculates the longitude of the true ascending node of the lunar orbit.
+/// The periodic correction to the mean node is taken from Meeus, Chapter 47.
+/// The returned value will be in degree, referred to the apparent equinox as the longitudes.
+pub fn ascending_node_longitude(jd: f64) -> f64 {
+    let jd = JulianDate::<Ut1>::new(jd).to_tt().value();
+    let jc = julian_century(jd);
+    let args = FundamentalArguments::at(jc);
+    let mean_node = 125.0445479 - 1934.1362891 * jc + 0.0020754 * jc * jc + jc * jc * jc / 467441.0
+        - jc * jc * jc * jc / 60616000.0;
+
+    let (d, m, mp, f) = (
+        args.elongation.to_radians(),
+        args.sun_anomaly.to_radians(),
+        args.moon_anomaly.to_radians(),
+        args.latitude_argument.to_radians(),
+    );
+    let correction = -1.4979 * sin(2.0 * (d - f)) - 0.1500 * sin(m) - 0.1226 * sin(2.0 * d)
+        + 0.1176 * sin(2.0 * f)
+        - 0.0801 * sin(2.0 * (f - mp));
+    normalize(mean_node + correction + nutation_in_longitude(jd))
+}
+
+/// Calculates the distance between the centers of the earth and the moon.
+/// The returned value will be in kilometer.
+pub fn moon_distance(jd: f64) -> f64 {
+    let jd = JulianDate::<Ut1>::new(jd).to_tt().value();
+    let args = FundamentalArguments::at(julian_century(jd));
+    MEAN_DISTANCE + args.sum_terms(DISTANCE_TERMS, cos) / 1e3
+}
@@ -0,0 +1,99 @@
+//! Lane-wise summation of the perturbations, enabled by `simd` feature.
+//! Arguments are processed in fixed-width lanes with a branch-free cosine made of
+//! additions, multiplications and comparisons only, so that the compiler emits packed
+//! SIMD instructions without `std::simd` (which is unstable) or external crates.
+
+use super::LongitudePerturbation;
+use crate::astro::math::powf;
+
+/// Number of arguments processed at once. 4 lanes fill a 256-bit register.
+const LANES: usize = 4;
+
+/// Adding and subtracting this rounds a value less than 2^51 to the nearest integer.
+const ROUNDING_MAGIC: f64 = 6755399441055744.0;
+
+/// Taylor coefficients of cosine in r^2, accurate to 1e-17 for |r| <= π/4.
+const COS_COEFFICIENTS: [f64; 9] = [
+    1.0,
+    -1.0 / 2.0,
+    1.0 / 24.0,
+    -1.0 / 720.0,
+    1.0 / 40320.0,
+    -1.0 / 3628800.0,
+    1.0 / 479001600.0,
+    -1.0 / 87178291200.0,
+    1.0 / 20922789888000.0,
+];
+
+/// Taylor coefficients of sine divided by r in r^2, accurate to 1e-17 for |r| <= π/4.
+const SIN_COEFFICIENTS: [f64; 9] = [
+    1.0,
+    -1.0 / 6.0,
+    1.0 / 120.0,
+    -1.0 / 5040.0,
+    1.0 / 362880.0,
+    -1.0 / 39916800.0,
+    1.0 / 6227020800.0,
+    -1.0 / 1307674368000.0,
+    1.0 / 355687428096000.0,
+];
+
+/// Sums the perturbations into the buffer lane by lane.
+/// The trailing arguments which do not fill the lanes are padded.
+pub fn sum_perturbations(perts: &[LongitudePerturbation], jcs: &[f64], sums: &mut [f64]) {
+    for (jc_chunk, sum_chunk) in jcs.chunks(LANES).zip(sums.chunks_mut(LANES)) {
+        let mut jc = [0.0; LANES];
+        jc[..jc_chunk.len()].copy_from_slice(jc_chunk);
+
+        let mut sum = [0.0; LANES];
+        for &(amplitude, power, frequency, phase) in perts {
+            let mut argument = [0.0; LANES];
+            for lane in 0..LANES {
+                argument[lane] = frequency * jc[lane] + phase;
+            }
+            let cos = cos_degree(argument);
+            if power == 0.0 {
+                for lane in 0..LANES {
+                    sum[lane] += amplitude * cos[lane];
+                }
+            } else {
+                for lane in 0..LANES {
+                    sum[lane] += amplitude * powf(jc[lane], power) * cos[lane];
+                }
+            }
+        }
+
+        sum_chunk.copy_from_slice(&sum[..sum_chunk.len()]);
+    }
+}
+
+/// Calculates cosine of the angles in degree.
+/// Angles are reduced by quadrants in degree, which is exact for multiples of 90.
+fn cos_degree(degrees: [f64; LANES]) -> [f64; LANES] {
+    let mut result = [0.0; LANES];
+    for lane in 0..LANES {
+        let quadrant = round(degrees[lane] / 90.0);
+        let r = (degrees[lane] - quadrant * 90.0).to_radians();
+        let z = r * r;
+        let cos = horner(&COS_COEFFICIENTS, z);
+        let sin = r * horner(&SIN_COEFFICIENTS, z);
+
+        // cos(r + 90q) cycles through cos, -sin, -cos, sin, and the remainder is in -2..=2
+        let remainder = quadrant - round(quadrant / 4.0) * 4.0;
+        let even = remainder == 0.0 || remainder.abs() == 2.0;
+        let value = if even { cos } else { sin };
+        let negative = remainder.abs() == 2.0 || remainder == 1.0;
+        result[lane] = if negative { -value } else { value };
+    }
+    result
+}
+
+/// Rounds to the nearest integer without a library call.
+fn round(x: f64) -> f64 {
+    (x + ROUNDING_MAGIC) - ROUNDING_MAGIC
+}
+
+/// Evaluates the polynomial by Horner's method.
+fn horner(coefficients: &[f64], x: f64) -> f64 {
+    coefficients.iter().rev().fold(0.0, |acc, c| acc * x + c)
+}
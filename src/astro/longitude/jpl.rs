@@ -0,0 +1,285 @@
+//! Sun and moon longitude calculation by a JPL DE ephemeris.
+//! The ephemeris is read at the startup from a binary file in the little-endian DE4xx format,
+//! such as an excerpt of DE440 cut out by JPL's tools.
+
+use std::{convert::TryInto, fs, path::Path, sync::OnceLock};
+
+use anyhow::{bail, Context, Result};
+
+use crate::{
+    astro::{
+        angle::normalize,
+        coords::{nutation_in_longitude, obliquity},
+        julian::{from_julian_date, julian_century, scale::Ut1, JulianDate},
+        math::{atan2, sin_cos},
+    },
+    error::ConversionError,
+};
+
+/// Byte offset of the start, end and interval of records in the header.
+const RANGE_OFFSET: usize = 2652;
+
+/// Byte offset of the earth-moon mass ratio in the header.
+const EMRAT_OFFSET: usize = 2688;
+
+/// Byte offset of the coefficient pointers in the header.
+const POINTERS_OFFSET: usize = 2696;
+
+/// Count of bodies which have coefficient pointers in the header.
+const POINTER_COUNT: usize = 12;
+
+/// Index of the earth-moon barycenter in the pointers.
+const EARTH_MOON_BARYCENTER: usize = 2;
+
+/// Index of the geocentric moon in the pointers.
+const MOON: usize = 9;
+
+/// Index of the sun in the pointers.
+const SUN: usize = 10;
+
+/// Astronomical unit in kilometer.
+const ASTRONOMICAL_UNIT: f64 = 149597870.7;
+
+/// The loaded ephemeris.
+static EPHEMERIS: OnceLock<Ephemeris> = OnceLock::new();
+
+/// Represents Chebyshev coefficients of an ephemeris.
+#[derive(Debug, Clone, PartialEq)]
+struct Ephemeris {
+    /// Julian Date (TDB) of the start of the first record.
+    jd_start: f64,
+
+    /// Julian Date (TDB) of the end of the last record.
+    jd_end: f64,
+
+    /// Length of a record in days.
+    interval: f64,
+
+    /// Ratio of the mass of the earth to the moon.
+    earth_moon_ratio: f64,
+
+    /// Offset (1-based), count of coefficients and count of sub-intervals of each body.
+    pointers: [(usize, usize, usize); POINTER_COUNT],
+
+    /// Count of coefficients in a record.
+    coefficients: usize,
+
+    /// Coefficients of records after the header and the constants.
+    data: Vec<f64>,
+}
+
+impl Ephemeris {
+    /// Reads the ephemeris file.
+    fn read(path: &Path) -> Result<Ephemeris> {
+        let bytes = fs::read(path)
+            .with_context(|| format!("Failed to read JPL ephemeris {}", path.display()))?;
+
+        let jd_start = read_f64(&bytes, RANGE_OFFSET)?;
+        let jd_end = read_f64(&bytes, RANGE_OFFSET + 8)?;
+        let interval = read_f64(&bytes, RANGE_OFFSET + 16)?;
+        let earth_moon_ratio = read_f64(&bytes, EMRAT_OFFSET)?;
+        let mut pointers = [(0, 0, 0); POINTER_COUNT];
+        for (i, pointer) in pointers.iter_mut().enumerate() {
+            let offset = POINTERS_OFFSET + i * 12;
+            *pointer = (
+                read_i32(&bytes, offset)? as usize,
+                read_i32(&bytes, offset + 4)? as usize,
+                read_i32(&bytes, offset + 8)? as usize,
+            );
+        }
+        if !(interval > 0.0 && jd_end > jd_start) {
+            bail!(
+                "JPL ephemeris has invalid range: {} to {}",
+                jd_start,
+                jd_end
+            );
+        }
+
+        // Records follow the header and the constants, each of which has the same length
+        let records = ((jd_end - jd_start) / interval).round() as usize;
+        let record_bytes = bytes.len() / (records + 2);
+        if records == 0
+            || !bytes.len().is_multiple_of(records + 2)
+            || !record_bytes.is_multiple_of(8)
+        {
+            bail!(
+                "JPL ephemeris has {} bytes for {} records",
+                bytes.len(),
+                records
+            );
+        }
+        let coefficients = record_bytes / 8;
+        for &body in &[EARTH_MOON_BARYCENTER, MOON, SUN] {
+            let (offset, count, subintervals) = pointers[body];
+            if offset == 0 || count == 0 || offset - 1 + count * 3 * subintervals > coefficients {
+                bail!("JPL ephemeris has no coefficients of body {}", body);
+            }
+        }
+
+        let data: Vec<_> = bytes[record_bytes * 2..]
+            .chunks_exact(8)
+            .map(|b| f64::from_le_bytes(b.try_into().expect("Should be 8 bytes")))
+            .collect();
+        if data[0] != jd_start || data[1] != jd_start + interval {
+            bail!("JPL ephemeris is not in little-endian or its first record mismatches");
+        }
+
+        Ok(Ephemeris {
+            jd_start,
+            jd_end,
+            interval,
+            earth_moon_ratio,
+            pointers,
+            coefficients,
+            data,
+        })
+    }
+
+    /// Calculates the position of the body in kilometer in ICRF at the Julian Date (TDB).
+    fn position(&self, body: usize, jd: f64) -> [f64; 3] {
+        let records = self.data.len() / self.coefficients;
+        let index = (((jd - self.jd_start) / self.interval).floor() as usize).min(records - 1);
+        let record = &self.data[index * self.coefficients..(index + 1) * self.coefficients];
+
+        let (offset, count, subintervals) = self.pointers[body];
+        let length = self.interval / subintervals as f64;
+        let subinterval = (((jd - record[0]) / length).floor() as usize).min(subintervals - 1);
+        let t = 2.0 * (jd - record[0] - subinterval as f64 * length) / length - 1.0;
+
+        let base = offset - 1 + subinterval * count * 3;
+        let mut position = [0.0; 3];
+        for (axis, value) in position.iter_mut().enumerate() {
+            let start = base + axis * count;
+            *value = chebyshev(&record[start..start + count], t);
+        }
+        position
+    }
+
+    /// Calculates the position of the sun from the center of the earth in kilometer.
+    fn geocentric_sun(&self, jd: f64) -> [f64; 3] {
+        let barycenter = self.position(EARTH_MOON_BARYCENTER, jd);
+        let moon = self.position(MOON, jd);
+        let sun = self.position(SUN, jd);
+
+        let mut position = [0.0; 3];
+        for axis in 0..3 {
+            let earth = barycenter[axis] - moon[axis] / (1.0 + self.earth_moon_ratio);
+            position[axis] = sun[axis] - earth;
+        }
+        position
+    }
+}
+
+/// Loads the ephemeris file, which can be done only once.
+pub fn load(path: impl AsRef<Path>) -> Result<()> {
+    let ephemeris = Ephemeris::read(path.as_ref())?;
+    if EPHEMERIS.set(ephemeris).is_err() {
+        bail!("JPL ephemeris is already loaded");
+    }
+    Ok(())
+}
+
+/// Checks that the ephemeris is loaded and covers the range in Julian Date.
+pub fn ensure_covers(jd_from: f64, jd_to: f64) -> Result<()> {
+    let ephemeris = match EPHEMERIS.get() {
+        Some(e) => e,
+        None => {
+            return Err(ConversionError::UnsupportedOptions {
+                requested: "JPL model without ephemeris file".into(),
+            }
+            .into())
+        }
+    };
+
+    // ΔT is less than a day in any ephemeris
+    if jd_from - 1.0 < ephemeris.jd_start || jd_to + 1.0 > ephemeris.jd_end {
+        return Err(ConversionError::OutOfSupportedRange {
+            requested: format!("JD {} to {}", jd_from, jd_to),
+            min: from_julian_date(ephemeris.jd_start + 1.0)?
+                .date()
+                .naive_utc(),
+            max: from_julian_date(ephemeris.jd_end - 1.0)?.date().naive_utc(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Calculates the apparent sun longitude.
+/// The argument is in UT and converted into TT, which is used as TDB.
+/// The returned value will be in degree.
+pub fn sun_longitude(jd: f64) -> f64 {
+    let jd = JulianDate::<Ut1>::new(jd).to_tt().value();
+    let position = loaded().geocentric_sun(jd);
+
+    let distance = position.iter().map(|x| x * x).sum::<f64>().sqrt() / ASTRONOMICAL_UNIT;
+    let aberration = -20.4898 / 3600.0 / distance;
+    normalize(ecliptic_longitude_of_date(position, jd) + nutation_in_longitude(jd) + aberration)
+}
+
+/// Calculates the apparent moon longitude.
+/// The argument is in UT and converted into TT, which is used as TDB.
+/// The returned value will be in degree.
+pub fn moon_longitude(jd: f64) -> f64 {
+    let jd = JulianDate::<Ut1>::new(jd).to_tt().value();
+    let position = loaded().position(MOON, jd);
+    normalize(ecliptic_longitude_of_date(position, jd) + nutation_in_longitude(jd))
+}
+
+/// Gets the loaded ephemeris, which callers must have ensured.
+fn loaded() -> &'static Ephemeris {
+    EPHEMERIS
+        .get()
+        .expect("JPL ephemeris should be ensured to be loaded")
+}
+
+/// Converts the equatorial position in J2000.0 into the longitude on the mean ecliptic of date.
+/// The precession is taken from Meeus, Chapter 21, and the frame bias of ICRF is ignored.
+fn ecliptic_longitude_of_date(position: [f64; 3], jd: f64) -> f64 {
+    let jc = julian_century(jd);
+    let arcsec = |a: f64, b: f64, c: f64| ((a + (b + c * jc) * jc) * jc / 3600.0).to_radians();
+    let zeta = arcsec(2306.2181, 0.30188, 0.017998);
+    let z = arcsec(2306.2181, 1.09468, 0.018203);
+    let theta = arcsec(2004.3109, -0.42665, -0.041833);
+
+    let (sin_zeta, cos_zeta) = sin_cos(zeta);
+    let (sin_z, cos_z) = sin_cos(z);
+    let (sin_theta, cos_theta) = sin_cos(theta);
+    let [x0, y0, z0] = position;
+    let x = (cos_z * cos_theta * cos_zeta - sin_z * sin_zeta) * x0
+        - (cos_z * cos_theta * sin_zeta + sin_z * cos_zeta) * y0
+        - cos_z * sin_theta * z0;
+    let y = (sin_z * cos_theta * cos_zeta + cos_z * sin_zeta) * x0
+        + (cos_z * cos_zeta - sin_z * cos_theta * sin_zeta) * y0
+        - sin_z * sin_theta * z0;
+    let z = sin_theta * cos_zeta * x0 - sin_theta * sin_zeta * y0 + cos_theta * z0;
+
+    let (sin_epsilon, cos_epsilon) = sin_cos(obliquity(jd).to_radians());
+    atan2(y * cos_epsilon + z * sin_epsilon, x).to_degrees()
+}
+
+/// Evaluates the Chebyshev series at t in [-1, 1] by Clenshaw's algorithm.
+fn chebyshev(coefficients: &[f64], t: f64) -> f64 {
+    let (b1, b2) = coefficients
+        .iter()
+        .skip(1)
+        .rev()
+        .fold((0.0, 0.0), |(b1, b2), c| (2.0 * t * b1 - b2 + c, b1));
+    coefficients[0] + t * b1 - b2
+}
+
+/// Reads a little-endian `f64` at the byte offset.
+fn read_f64(bytes: &[u8], offset: usize) -> Result<f64> {
+    match bytes.get(offset..offset + 8) {
+        Some(b) => Ok(f64::from_le_bytes(b.try_into()?)),
+        None => bail!("JPL ephemeris is too short"),
+    }
+}
+
+/// Reads a little-endian `i32` at the byte offset.
+fn read_i32(bytes: &[u8], offset: usize) -> Result<i32> {
+    match bytes.get(offset..offset + 4) {
+        Some(b) => Ok(i32::from_le_bytes(b.try_into()?)),
+        None => bail!("JPL ephemeris is too short"),
+    }
+}
@@ -0,0 +1,162 @@
+//! Approximation of JCG78 longitudes by cubic interpolation of samples on coarse grids.
+//! Samples are cached across calculations, so solvers find roots on the approximation cheaply
+//! and evaluate the series only to refine them.
+
+use std::{
+    collections::{BTreeMap, VecDeque},
+    sync::RwLock,
+};
+
+use crate::{
+    astro::{
+        angle::{normalize, signed_difference},
+        longitude::{jcg78, LongitudeModel},
+    },
+    cache_stats::{CacheCounters, CacheStats},
+};
+
+/// Interval of sun samples in days. Interpolation errors are below 0.0001 degree.
+const SUN_INTERVAL: f64 = 4.0;
+
+/// Interval of moon samples in days. Interpolation errors are below 0.0001 degree.
+const MOON_INTERVAL: f64 = 0.5;
+
+/// Maximum count of samples of each body, which cover 700 years for the sun and 90 years for the moon.
+const MAX_SAMPLES: usize = 65536;
+
+/// Sun longitude samples.
+static SUN_SAMPLES: RwLock<Samples> = RwLock::new(Samples::new());
+
+/// Moon longitude samples.
+static MOON_SAMPLES: RwLock<Samples> = RwLock::new(Samples::new());
+
+/// Lookups of samples of both bodies.
+static SAMPLE_COUNTERS: CacheCounters = CacheCounters::new();
+
+/// Represents samples keyed by the index on the grid, evicted in the order of insertion.
+#[derive(Debug, Clone, PartialEq)]
+struct Samples {
+    values: BTreeMap<i64, f64>,
+    order: VecDeque<i64>,
+}
+
+impl Samples {
+    /// Constructs empty samples.
+    const fn new() -> Samples {
+        Samples {
+            values: BTreeMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Inserts the sample, returning the count of evicted samples.
+    fn insert(&mut self, index: i64, value: f64) -> u64 {
+        if self.values.insert(index, value).is_some() {
+            return 0;
+        }
+        self.order.push_back(index);
+
+        let mut evicted = 0;
+        while self.order.len() > MAX_SAMPLES {
+            let oldest = self.order.pop_front().expect("Should have samples");
+            self.values.remove(&oldest);
+            evicted += 1;
+        }
+        evicted
+    }
+}
+
+/// JCG78 longitudes interpolated from the cached samples.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct SampledJcg78;
+
+impl LongitudeModel for SampledJcg78 {
+    fn sun_longitude(&self, jd: f64) -> f64 {
+        interpolate(&SUN_SAMPLES, SUN_INTERVAL, jcg78::sun_longitudes, jd)
+    }
+
+    fn moon_longitude(&self, jd: f64) -> f64 {
+        interpolate(&MOON_SAMPLES, MOON_INTERVAL, jcg78::moon_longitudes, jd)
+    }
+}
+
+/// Interpolates the longitude by the cubic polynomial through the 4 samples around the instant.
+fn interpolate(
+    samples: &RwLock<Samples>,
+    interval: f64,
+    evaluate: fn(&[f64]) -> Vec<f64>,
+    jd: f64,
+) -> f64 {
+    let index = (jd / interval).floor() as i64;
+    let values = sample(samples, interval, evaluate, index - 1);
+
+    // Longitudes are unwrapped so that the polynomial does not jump at 360 degree
+    let mut unwrapped = values;
+    for i in 1..unwrapped.len() {
+        unwrapped[i] = unwrapped[i - 1] + signed_difference(values[i], values[i - 1]);
+    }
+
+    // Lagrange polynomial through the samples at -1, 0, 1 and 2
+    let t = jd / interval - index as f64;
+    let weights = [
+        -t * (t - 1.0) * (t - 2.0) / 6.0,
+        (t + 1.0) * (t - 1.0) * (t - 2.0) / 2.0,
+        -(t + 1.0) * t * (t - 2.0) / 2.0,
+        (t + 1.0) * t * (t - 1.0) / 6.0,
+    ];
+    let longitude = weights.iter().zip(&unwrapped).map(|(w, v)| w * v).sum();
+    normalize(longitude)
+}
+
+/// Gets 4 consecutive samples from the index, evaluating the missing ones at once.
+fn sample(
+    samples: &RwLock<Samples>,
+    interval: f64,
+    evaluate: fn(&[f64]) -> Vec<f64>,
+    first_index: i64,
+) -> [f64; 4] {
+    let mut values = [0.0; 4];
+    let mut missing = vec![];
+    {
+        let samples = samples.read().expect("Samples should not be poisoned");
+        for (i, value) in values.iter_mut().enumerate() {
+            let index = first_index + i as i64;
+            let cached = samples.values.get(&index);
+            SAMPLE_COUNTERS.record(cached.is_some());
+            match cached {
+                Some(cached) => *value = *cached,
+                None => missing.push(i),
+            }
+        }
+    }
+    if missing.is_empty() {
+        return values;
+    }
+
+    let jds: Vec<_> = missing
+        .iter()
+        .map(|&i| (first_index + i as i64) as f64 * interval)
+        .collect();
+    let evaluated = evaluate(&jds);
+    let mut samples = samples.write().expect("Samples should not be poisoned");
+    for (&i, &value) in missing.iter().zip(&evaluated) {
+        values[i] = value;
+        let evicted = samples.insert(first_index + i as i64, value);
+        SAMPLE_COUNTERS.record_evictions(evicted);
+    }
+    values
+}
+
+/// Gets the counters of the samples of both bodies.
+pub fn cache_stats() -> CacheStats {
+    let entries = [&SUN_SAMPLES, &MOON_SAMPLES]
+        .iter()
+        .map(|s| {
+            s.read()
+                .expect("Samples should not be poisoned")
+                .order
+                .len()
+        })
+        .sum();
+    SAMPLE_COUNTERS.stats(entries)
+}
@@ -0,0 +1,85 @@
+//! Contains elementary functions used in the astronomical calculations.
+//! They are evaluated by the platform math library, which may differ in the last bits by target.
+//! With `deterministic` feature they are evaluated by libm in Rust instead,
+//! so that the same input yields bit-identical results on x86, ARM and wasm.
+//! Square roots and arithmetic are correctly rounded by IEEE 754, so they are left as they are.
+
+/// Calculates sine of the angle in radian.
+pub fn sin(x: f64) -> f64 {
+    #[cfg(feature = "deterministic")]
+    return libm::sin(x);
+    #[cfg(not(feature = "deterministic"))]
+    return x.sin();
+}
+
+/// Calculates cosine of the angle in radian.
+pub fn cos(x: f64) -> f64 {
+    #[cfg(feature = "deterministic")]
+    return libm::cos(x);
+    #[cfg(not(feature = "deterministic"))]
+    return x.cos();
+}
+
+/// Calculates sine and cosine of the angle in radian at once.
+pub fn sin_cos(x: f64) -> (f64, f64) {
+    #[cfg(feature = "deterministic")]
+    return libm::sincos(x);
+    #[cfg(not(feature = "deterministic"))]
+    return x.sin_cos();
+}
+
+/// Calculates tangent of the angle in radian.
+pub fn tan(x: f64) -> f64 {
+    #[cfg(feature = "deterministic")]
+    return libm::tan(x);
+    #[cfg(not(feature = "deterministic"))]
+    return x.tan();
+}
+
+/// Calculates arcsine in radian.
+pub fn asin(x: f64) -> f64 {
+    #[cfg(feature = "deterministic")]
+    return libm::asin(x);
+    #[cfg(not(feature = "deterministic"))]
+    return x.asin();
+}
+
+/// Calculates arccosine in radian.
+pub fn acos(x: f64) -> f64 {
+    #[cfg(feature = "deterministic")]
+    return libm::acos(x);
+    #[cfg(not(feature = "deterministic"))]
+    return x.acos();
+}
+
+/// Calculates arctangent in radian.
+pub fn atan(x: f64) -> f64 {
+    #[cfg(feature = "deterministic")]
+    return libm::atan(x);
+    #[cfg(not(feature = "deterministic"))]
+    return x.atan();
+}
+
+/// Calculates the angle of the point (x, y) in radian, from -π to π.
+pub fn atan2(y: f64, x: f64) -> f64 {
+    #[cfg(feature = "deterministic")]
+    return libm::atan2(y, x);
+    #[cfg(not(feature = "deterministic"))]
+    return y.atan2(x);
+}
+
+/// Raises to the power.
+pub fn powf(x: f64, power: f64) -> f64 {
+    #[cfg(feature = "deterministic")]
+    return libm::pow(x, power);
+    #[cfg(not(feature = "deterministic"))]
+    return x.powf(power);
+}
+
+/// Raises to the integer power.
+pub fn powi(x: f64, power: i32) -> f64 {
+    #[cfg(feature = "deterministic")]
+    return libm::pow(x, power as f64);
+    #[cfg(not(feature = "deterministic"))]
+    return x.powi(power);
+}
@@ -1,6 +1,179 @@
+pub mod angle;
+pub mod coords;
+pub mod delta_t;
+pub mod eclipse;
+pub mod events;
 pub mod julian;
+pub mod leap_second;
+pub mod math;
+pub mod moon;
+pub mod planet;
+pub mod riseset;
+pub mod solver;
+pub mod sun;
 pub mod longitude {
+    pub mod elp2000;
     pub mod jcg78;
+    pub mod jpl;
+    pub mod sampled;
+    pub mod vsop87;
     // pub mod jcgnew;
     // pub mod usno;
+
+    use anyhow::Result;
+    use serde::Deserialize;
+
+    use crate::astro::solver::{default_tolerance, tolerance_from_seconds};
+
+    /// Represents a trade-off between accuracy and speed of evaluations.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum Precision {
+        /// Truncates small terms of JCG78 series and loosens solvers, accurate to several minutes.
+        Fast,
+
+        /// Evaluates the full series and solves to a millisecond.
+        #[default]
+        Accurate,
+    }
+
+    impl Precision {
+        /// Gets the tolerance of solvers in days.
+        pub fn tolerance(self) -> f64 {
+            match self {
+                Precision::Fast => 1e-5,
+                Precision::Accurate => default_tolerance(),
+            }
+        }
+    }
+
+    /// Represents a model of the sun longitude.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum SunModel {
+        /// Japan Coast Guard's approximation in 1978, accurate to minutes.
+        #[default]
+        Jcg78,
+
+        /// Truncated VSOP87, accurate to seconds.
+        Vsop87,
+
+        /// JPL DE ephemeris loaded at the startup.
+        Jpl,
+    }
+
+    impl SunModel {
+        /// Calculates the sun longitude in degree in the precision.
+        pub fn sun_longitude(self, jd: f64, precision: Precision) -> f64 {
+            match self {
+                SunModel::Jcg78 => jcg78::sun_longitude_in(jd, precision),
+                SunModel::Vsop87 => vsop87::sun_longitude(jd),
+                SunModel::Jpl => jpl::sun_longitude(jd),
+            }
+        }
+    }
+
+    /// Represents a model of the moon longitude.
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    pub enum MoonModel {
+        /// Japan Coast Guard's approximation in 1978.
+        #[default]
+        Jcg78,
+
+        /// Truncated ELP-2000/82 with 60 periodic terms.
+        Elp2000,
+
+        /// JPL DE ephemeris loaded at the startup.
+        Jpl,
+    }
+
+    /// Provides apparent longitudes of the sun and the moon.
+    pub trait LongitudeModel {
+        /// Calculates the sun longitude in degree.
+        fn sun_longitude(&self, jd: f64) -> f64;
+
+        /// Calculates the moon longitude in degree.
+        fn moon_longitude(&self, jd: f64) -> f64;
+
+        /// Gets the tolerance in days for solvers using this model.
+        fn tolerance(&self) -> f64 {
+            default_tolerance()
+        }
+
+        /// Gets a cheaper approximation of this model, if any.
+        /// Solvers find roots on it first and refine them on this model.
+        fn approximation(&self) -> Option<&dyn LongitudeModel> {
+            None
+        }
+
+        /// Calculates the sun longitudes at once, in the same order as the arguments.
+        /// Models which can evaluate series in batch should override this.
+        fn sun_longitudes(&self, jds: &[f64]) -> Vec<f64> {
+            jds.iter().map(|&jd| self.sun_longitude(jd)).collect()
+        }
+
+        /// Calculates the moon longitudes at once, in the same order as the arguments.
+        /// Models which can evaluate series in batch should override this.
+        fn moon_longitudes(&self, jds: &[f64]) -> Vec<f64> {
+            jds.iter().map(|&jd| self.moon_longitude(jd)).collect()
+        }
+    }
+
+    /// Represents a combination of the sun and moon models in the precision.
+    #[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+    pub struct Models {
+        #[serde(default)]
+        pub sun_model: SunModel,
+        #[serde(default)]
+        pub moon_model: MoonModel,
+        #[serde(default)]
+        pub precision: Precision,
+        /// Tolerance of solvers in seconds, which overrides the one of the precision.
+        /// Numbers cannot be flattened from query strings, so handlers set this.
+        #[serde(skip)]
+        pub tolerance: Option<f64>,
+    }
+
+    impl Models {
+        /// Checks that the models can evaluate the range in Julian Date with the tolerance.
+        /// Only JPL model is limited, by the loaded ephemeris.
+        pub fn ensure_covers(&self, jd_from: f64, jd_to: f64) -> Result<()> {
+            if let Some(seconds) = self.tolerance {
+                tolerance_from_seconds(seconds)?;
+            }
+            if self.sun_model == SunModel::Jpl || self.moon_model == MoonModel::Jpl {
+                jpl::ensure_covers(jd_from, jd_to)?;
+            }
+            Ok(())
+        }
+    }
+
+    impl LongitudeModel for Models {
+        fn sun_longitude(&self, jd: f64) -> f64 {
+            self.sun_model.sun_longitude(jd, self.precision)
+        }
+
+        fn moon_longitude(&self, jd: f64) -> f64 {
+            self.moon_model.moon_longitude(jd, self.precision)
+        }
+
+        fn tolerance(&self) -> f64 {
+            match self.tolerance {
+                Some(seconds) => seconds / 86400.0,
+                None => self.precision.tolerance(),
+            }
+        }
+    }
+
+    impl MoonModel {
+        /// Calculates the moon longitude in degree in the precision.
+        pub fn moon_longitude(self, jd: f64, precision: Precision) -> f64 {
+            match self {
+                MoonModel::Jcg78 => jcg78::moon_longitude_in(jd, precision),
+                MoonModel::Elp2000 => elp2000::moon_longitude(jd),
+                MoonModel::Jpl => jpl::moon_longitude(jd),
+            }
+        }
+    }
 }
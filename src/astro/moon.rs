@@ -0,0 +1,264 @@
+//! Contains lunar age (月齢), phase and distance calculations.
+
+use anyhow::Result;
+
+use crate::{
+    astro::{
+        angle::normalize,
+        coords::{ecliptic_to_equatorial, geocentric_to_topocentric},
+        julian::local_apparent_sidereal_time,
+        longitude::{
+            elp2000::{ascending_node_longitude, moon_distance, moon_latitude},
+            jcg78::{moon_longitude, sun_longitude},
+        },
+        math::{asin, cos},
+        riseset::Observer,
+        solver::find_minimum,
+    },
+    error::ConversionError,
+    tempo::calculate_leading_saku,
+};
+
+/// Mean length of synodic month in days.
+pub const SYNODIC_MONTH: f64 = 29.530589;
+
+/// Full moons closer than this distance in kilometer are called supermoons.
+pub const SUPERMOON_DISTANCE: f64 = 360000.0;
+
+/// Mean radius of the moon in kilometer.
+const MOON_RADIUS: f64 = 1737.4;
+
+/// Full moons farther than this distance in kilometer are called micromoons.
+pub const MICROMOON_DISTANCE: f64 = 405000.0;
+
+/// Maximum count of half-day steps to walk to an apsis, which covers two anomalistic months.
+const MAX_APSIS_STEPS: usize = 110;
+
+/// Represents an apsis of the lunar orbit.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Apsis {
+    /// The nearest point, 近地点.
+    Perigee,
+
+    /// The farthest point, 遠地点.
+    Apogee,
+}
+
+impl Apsis {
+    /// Gets English name.
+    pub fn to_english(self) -> &'static str {
+        match self {
+            Apsis::Perigee => "perigee",
+            Apsis::Apogee => "apogee",
+        }
+    }
+}
+
+/// Represents a node of the lunar orbit, where the moon crosses the ecliptic.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Node {
+    /// The moon goes north, 昇交点.
+    Ascending,
+
+    /// The moon goes south, 降交点.
+    Descending,
+}
+
+impl Node {
+    /// Gets Japanese string.
+    pub fn to_japanese(self) -> &'static str {
+        match self {
+            Node::Ascending => "昇交点",
+            Node::Descending => "降交点",
+        }
+    }
+
+    /// Gets English translation.
+    pub fn to_english(self) -> &'static str {
+        match self {
+            Node::Ascending => "ascending node",
+            Node::Descending => "descending node",
+        }
+    }
+
+    /// Calculates the ecliptic longitude of this node in degree.
+    pub fn longitude(self, jd: f64) -> f64 {
+        match self {
+            Node::Ascending => ascending_node_longitude(jd),
+            Node::Descending => normalize(ascending_node_longitude(jd) + 180.0),
+        }
+    }
+}
+
+/// Represents apparent size class of a full moon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FullMoonSize {
+    Supermoon,
+    Regular,
+    Micromoon,
+}
+
+impl FullMoonSize {
+    /// Classifies from the distance of the moon in kilometer.
+    pub fn from_distance(distance: f64) -> FullMoonSize {
+        if distance < SUPERMOON_DISTANCE {
+            FullMoonSize::Supermoon
+        } else if distance > MICROMOON_DISTANCE {
+            FullMoonSize::Micromoon
+        } else {
+            FullMoonSize::Regular
+        }
+    }
+
+    /// Gets English name.
+    pub fn to_english(self) -> &'static str {
+        match self {
+            FullMoonSize::Supermoon => "supermoon",
+            FullMoonSize::Regular => "regular",
+            FullMoonSize::Micromoon => "micromoon",
+        }
+    }
+}
+
+/// Represents a named phase of the moon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MoonPhase {
+    NewMoon,
+    WaxingCrescent,
+    FirstQuarter,
+    WaxingGibbous,
+    FullMoon,
+    WaningGibbous,
+    LastQuarter,
+    WaningCrescent,
+}
+
+#[allow(dead_code)]
+impl MoonPhase {
+    /// Gets Japanese string.
+    pub fn to_japanese(self) -> &'static str {
+        match self {
+            MoonPhase::NewMoon => "新月",
+            MoonPhase::WaxingCrescent => "三日月",
+            MoonPhase::FirstQuarter => "上弦",
+            MoonPhase::WaxingGibbous => "十三夜",
+            MoonPhase::FullMoon => "満月",
+            MoonPhase::WaningGibbous => "寝待月",
+            MoonPhase::LastQuarter => "下弦",
+            MoonPhase::WaningCrescent => "有明月",
+        }
+    }
+
+    /// Gets Hepburn romanization.
+    pub fn to_romaji(self) -> &'static str {
+        match self {
+            MoonPhase::NewMoon => "Shingetsu",
+            MoonPhase::WaxingCrescent => "Mikazuki",
+            MoonPhase::FirstQuarter => "Jōgen",
+            MoonPhase::WaxingGibbous => "Jūsan'ya",
+            MoonPhase::FullMoon => "Mangetsu",
+            MoonPhase::WaningGibbous => "Nemachizuki",
+            MoonPhase::LastQuarter => "Kagen",
+            MoonPhase::WaningCrescent => "Ariakezuki",
+        }
+    }
+
+    /// Gets English translation.
+    pub fn to_english(self) -> &'static str {
+        match self {
+            MoonPhase::NewMoon => "new moon",
+            MoonPhase::WaxingCrescent => "waxing crescent",
+            MoonPhase::FirstQuarter => "first quarter",
+            MoonPhase::WaxingGibbous => "waxing gibbous",
+            MoonPhase::FullMoon => "full moon",
+            MoonPhase::WaningGibbous => "waning gibbous",
+            MoonPhase::LastQuarter => "last quarter",
+            MoonPhase::WaningCrescent => "waning crescent",
+        }
+    }
+
+    /// Classifies from lunar age in days, dividing the synodic month into 8 phases.
+    pub fn from_lunar_age(age: f64) -> MoonPhase {
+        match (age / SYNODIC_MONTH * 8.0).round() as usize % 8 {
+            0 => MoonPhase::NewMoon,
+            1 => MoonPhase::WaxingCrescent,
+            2 => MoonPhase::FirstQuarter,
+            3 => MoonPhase::WaxingGibbous,
+            4 => MoonPhase::FullMoon,
+            5 => MoonPhase::WaningGibbous,
+            6 => MoonPhase::LastQuarter,
+            _ => MoonPhase::WaningCrescent,
+        }
+    }
+}
+
+/// Calculates lunar age, the days since the preceding saku, with Julian Date.
+pub fn age(jd: f64) -> Result<f64> {
+    Ok(jd - calculate_leading_saku(jd)?)
+}
+
+/// Calculates the illuminated fraction of the lunar disk, from 0.0 (new) to 1.0 (full).
+/// The phase angle is approximated by the supplement of the elongation.
+pub fn illumination(jd: f64) -> f64 {
+    let longitude_difference = (moon_longitude(jd) - sun_longitude(jd)).to_radians();
+    let cos_elongation = cos(latitude(jd).to_radians()) * cos(longitude_difference);
+    (1.0 - cos_elongation) / 2.0
+}
+
+/// Calculates the right ascension and declination of the moon in degree seen from the center of the earth.
+pub fn geocentric_position(jd: f64) -> (f64, f64) {
+    ecliptic_to_equatorial(jd, moon_longitude(jd), moon_latitude(jd))
+}
+
+/// Calculates the right ascension and declination of the moon in degree seen from the observer,
+/// corrected by the parallax which reaches about a degree on the horizon.
+pub fn topocentric_position(jd: f64, observer: &Observer) -> (f64, f64) {
+    let (right_ascension, declination) = geocentric_position(jd);
+    geocentric_to_topocentric(
+        right_ascension,
+        declination,
+        distance(jd),
+        observer.latitude,
+        local_apparent_sidereal_time(jd, observer.longitude),
+    )
+}
+
+/// Calculates the apparent semi-diameter of the moon in degree seen from the center of the earth.
+pub fn semi_diameter(jd: f64) -> f64 {
+    asin(MOON_RADIUS / distance(jd)).to_degrees()
+}
+
+/// Calculates the ecliptic latitude of the moon in degree.
+pub fn latitude(jd: f64) -> f64 {
+    moon_latitude(jd)
+}
+
+/// Calculates the distance of the moon in kilometer.
+pub fn distance(jd: f64) -> f64 {
+    moon_distance(jd)
+}
+
+/// Finds the first apsis of the kind after the instant, with Julian Date.
+pub fn next_apsis(jd_from: f64, apsis: Apsis) -> Result<f64> {
+    // Perigee is the minimum of distance, and apogee is the maximum
+    let sign = match apsis {
+        Apsis::Perigee => 1.0,
+        Apsis::Apogee => -1.0,
+    };
+    let objective = |jd: f64| sign * distance(jd);
+
+    // Walks by half a day until the objective starts increasing, within two anomalistic months
+    let step = 0.5;
+    let mut jd = jd_from;
+    let mut steps = 0;
+    while !(objective(jd) < objective(jd - step) && objective(jd) <= objective(jd + step)) {
+        if steps >= MAX_APSIS_STEPS {
+            return Err(ConversionError::SolverDidNotConverge { jd: jd_from }.into());
+        }
+        jd += step;
+        steps += 1;
+    }
+
+    find_minimum(jd - step, jd + step, 1.0 / 1440.0, objective)
+        .map_err(|_| ConversionError::SolverDidNotConverge { jd }.into())
+}
@@ -0,0 +1,406 @@
+//! Contains planetary positions by Keplerian elements.
+//! Elements are taken from Standish, "Keplerian Elements for Approximate Positions of the Major Planets",
+//! valid from 1800 to 2050 and accurate to arcminutes.
+
+use crate::astro::{
+    angle::{normalize, normalize_signed, Degree, Radian},
+    coords::nutation_in_longitude,
+    julian::julian_century,
+    longitude::jcg78::{moon_longitude, sun_longitude},
+    math::{atan2, cos, sin},
+    solver::find_root,
+};
+
+/// Light time for 1 AU in days.
+const LIGHT_TIME_PER_AU: f64 = 0.0057755183;
+
+/// General precession in longitude per Julian century in degree.
+const PRECESSION_PER_CENTURY: f64 = 1.396971;
+
+/// Keplerian elements and their rates per century as `(a, e, I, L, ϖ, Ω)`.
+/// Angles are in degree and `a` is in AU.
+type Elements = ([f64; 6], [f64; 6]);
+
+/// Elements of the Earth-Moon barycenter.
+const EARTH: Elements = (
+    [
+        1.00000261,
+        0.01671123,
+        -0.00001531,
+        100.46457166,
+        102.93768193,
+        0.0,
+    ],
+    [
+        0.00000562,
+        -0.00004392,
+        -0.01294668,
+        35999.37244981,
+        0.32327364,
+        0.0,
+    ],
+);
+
+/// Represents a planet visible to the naked eye.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Planet {
+    Mercury,
+    Venus,
+    Mars,
+    Jupiter,
+    Saturn,
+}
+
+impl Planet {
+    /// All visible planets in order from the sun.
+    pub const ALL: [Planet; 5] = [
+        Planet::Mercury,
+        Planet::Venus,
+        Planet::Mars,
+        Planet::Jupiter,
+        Planet::Saturn,
+    ];
+
+    /// Gets Japanese string.
+    pub fn to_japanese(self) -> &'static str {
+        match self {
+            Planet::Mercury => "水星",
+            Planet::Venus => "金星",
+            Planet::Mars => "火星",
+            Planet::Jupiter => "木星",
+            Planet::Saturn => "土星",
+        }
+    }
+
+    /// Gets Hepburn romanization.
+    pub fn to_romaji(self) -> &'static str {
+        match self {
+            Planet::Mercury => "Suisei",
+            Planet::Venus => "Kinsei",
+            Planet::Mars => "Kasei",
+            Planet::Jupiter => "Mokusei",
+            Planet::Saturn => "Dosei",
+        }
+    }
+
+    /// Gets English translation.
+    pub fn to_english(self) -> &'static str {
+        match self {
+            Planet::Mercury => "Mercury",
+            Planet::Venus => "Venus",
+            Planet::Mars => "Mars",
+            Planet::Jupiter => "Jupiter",
+            Planet::Saturn => "Saturn",
+        }
+    }
+
+    /// Whether the orbit is inside that of the earth.
+    pub fn is_inferior(self) -> bool {
+        matches!(self, Planet::Mercury | Planet::Venus)
+    }
+
+    fn elements(self) -> Elements {
+        match self {
+            Planet::Mercury => (
+                [
+                    0.38709927,
+                    0.20563593,
+                    7.00497902,
+                    252.25032350,
+                    77.45779628,
+                    48.33076593,
+                ],
+                [
+                    0.00000037,
+                    0.00001906,
+                    -0.00594749,
+                    149472.67411175,
+                    0.16047689,
+                    -0.12534081,
+                ],
+            ),
+            Planet::Venus => (
+                [
+                    0.72333566,
+                    0.00677672,
+                    3.39467605,
+                    181.97909950,
+                    131.60246718,
+                    76.67984255,
+                ],
+                [
+                    0.00000390,
+                    -0.00004107,
+                    -0.00078890,
+                    58517.81538729,
+                    0.00268329,
+                    -0.27769418,
+                ],
+            ),
+            Planet::Mars => (
+                [
+                    1.52371034,
+                    0.09339410,
+                    1.84969142,
+                    -4.55343205,
+                    -23.94362959,
+                    49.55953891,
+                ],
+                [
+                    0.00001847,
+                    0.00007882,
+                    -0.00813131,
+                    19140.30268499,
+                    0.44441088,
+                    -0.29257343,
+                ],
+            ),
+            Planet::Jupiter => (
+                [
+                    5.20288700,
+                    0.04838624,
+                    1.30439695,
+                    34.39644051,
+                    14.72847983,
+                    100.47390909,
+                ],
+                [
+                    -0.00011607,
+                    -0.00013253,
+                    -0.00183714,
+                    3034.74612775,
+                    0.21252668,
+                    0.20469106,
+                ],
+            ),
+            Planet::Saturn => (
+                [
+                    9.53667594,
+                    0.05386179,
+                    2.48599187,
+                    49.95424423,
+                    92.59887831,
+                    113.66242448,
+                ],
+                [
+                    -0.00125060,
+                    -0.00050991,
+                    0.00193609,
+                    1222.49362201,
+                    -0.41897216,
+                    -0.28867794,
+                ],
+            ),
+        }
+    }
+}
+
+/// Represents a geocentric ecliptic position of a planet.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlanetPosition {
+    /// Apparent longitude of the date in degree.
+    pub longitude: f64,
+
+    /// Latitude in degree.
+    pub latitude: f64,
+
+    /// Distance from the earth in AU.
+    pub distance: f64,
+}
+
+/// Calculates heliocentric ecliptic rectangular coordinates of J2000.0 in AU.
+fn heliocentric(elements: Elements, jd: f64) -> [f64; 3] {
+    let jc = julian_century(jd);
+    let (base, rates) = elements;
+    let [a, e, i, l, varpi, omega] = [0, 1, 2, 3, 4, 5].map(|n| base[n] + rates[n] * jc);
+
+    // Solves Kepler's equation by Newton's method
+    let mean_anomaly = Radian::from(Degree(l - varpi).normalized()).0;
+    let mut eccentric_anomaly = mean_anomaly + e * sin(mean_anomaly);
+    for _ in 0..10 {
+        let delta = (eccentric_anomaly - e * sin(eccentric_anomaly) - mean_anomaly)
+            / (1.0 - e * cos(eccentric_anomaly));
+        eccentric_anomaly -= delta;
+        if delta.abs() < 1e-12 {
+            break;
+        }
+    }
+
+    let x_orbit = a * (cos(eccentric_anomaly) - e);
+    let y_orbit = a * (1.0 - e * e).sqrt() * sin(eccentric_anomaly);
+
+    let argument = (varpi - omega).to_radians();
+    let (i, omega) = (i.to_radians(), omega.to_radians());
+    let (cw, sw, co, so, ci, si) = (
+        cos(argument),
+        sin(argument),
+        cos(omega),
+        sin(omega),
+        cos(i),
+        sin(i),
+    );
+    [
+        (cw * co - sw * so * ci) * x_orbit + (-sw * co - cw * so * ci) * y_orbit,
+        (cw * so + sw * co * ci) * x_orbit + (-sw * so + cw * co * ci) * y_orbit,
+        (sw * si) * x_orbit + (cw * si) * y_orbit,
+    ]
+}
+
+/// Calculates the geocentric position of the planet, with correction of light time.
+pub fn position(planet: Planet, jd: f64) -> PlanetPosition {
+    let earth = heliocentric(EARTH, jd);
+    let geocentric = |jd_emitted: f64| {
+        let planet = heliocentric(planet.elements(), jd_emitted);
+        [
+            planet[0] - earth[0],
+            planet[1] - earth[1],
+            planet[2] - earth[2],
+        ]
+    };
+    let norm = |v: [f64; 3]| (v[0] * v[0] + v[1] * v[1] + v[2] * v[2]).sqrt();
+
+    let distance = norm(geocentric(jd));
+    let [x, y, z] = geocentric(jd - distance * LIGHT_TIME_PER_AU);
+
+    // Precession from J2000.0 and nutation are applied to the longitude
+    let longitude = atan2(y, x).to_degrees()
+        + PRECESSION_PER_CENTURY * julian_century(jd)
+        + nutation_in_longitude(jd);
+    PlanetPosition {
+        longitude: normalize(longitude),
+        latitude: atan2(z, (x * x + y * y).sqrt()).to_degrees(),
+        distance,
+    }
+}
+
+/// Represents a kind of planetary phenomena.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanetEventKind {
+    /// Inferior conjunction (内合) of Mercury or Venus.
+    InferiorConjunction,
+
+    /// Superior conjunction (外合) of Mercury or Venus.
+    SuperiorConjunction,
+
+    /// Conjunction (合) of an outer planet.
+    Conjunction,
+
+    /// Opposition (衝) of an outer planet.
+    Opposition,
+
+    /// Conjunction with the moon in longitude.
+    MoonConjunction,
+}
+
+impl PlanetEventKind {
+    /// Gets Japanese string.
+    pub fn to_japanese(self) -> &'static str {
+        match self {
+            PlanetEventKind::InferiorConjunction => "内合",
+            PlanetEventKind::SuperiorConjunction => "外合",
+            PlanetEventKind::Conjunction => "合",
+            PlanetEventKind::Opposition => "衝",
+            PlanetEventKind::MoonConjunction => "月と接近",
+        }
+    }
+
+    /// Gets English translation.
+    pub fn to_english(self) -> &'static str {
+        match self {
+            PlanetEventKind::InferiorConjunction => "inferior conjunction",
+            PlanetEventKind::SuperiorConjunction => "superior conjunction",
+            PlanetEventKind::Conjunction => "conjunction",
+            PlanetEventKind::Opposition => "opposition",
+            PlanetEventKind::MoonConjunction => "conjunction with the moon",
+        }
+    }
+}
+
+/// Represents a planetary phenomenon.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct PlanetEvent {
+    pub planet: Planet,
+    pub kind: PlanetEventKind,
+
+    /// Instant in Julian Date.
+    pub jd: f64,
+
+    /// Latitude of the planet at the instant in degree.
+    pub latitude: f64,
+}
+
+/// Finds instants where the angle difference crosses zero upward, scanning by the step.
+fn find_crossings(
+    jd_from: f64,
+    jd_to: f64,
+    step: f64,
+    difference: impl Fn(f64) -> f64,
+) -> Vec<f64> {
+    let signed = |jd: f64| normalize_signed(difference(jd));
+    let mut crossings = vec![];
+    let mut jd = jd_from;
+    let mut last = signed(jd);
+    while jd < jd_to {
+        let next = signed(jd + step);
+        // Excludes jumps at the opposite side
+        if last < 0.0 && next >= 0.0 && next - last < 90.0 {
+            if let Ok(root) = find_root(jd, jd + step, signed) {
+                crossings.push(root);
+            }
+        }
+        jd += step;
+        last = next;
+    }
+    crossings
+}
+
+/// Searches phenomena of the planet in the range.
+pub fn events(planet: Planet, jd_from: f64, jd_to: f64) -> Vec<PlanetEvent> {
+    let longitude = |jd: f64| position(planet, jd).longitude;
+    let event = |kind: PlanetEventKind, jd: f64| PlanetEvent {
+        planet,
+        kind,
+        jd,
+        latitude: position(planet, jd).latitude,
+    };
+
+    // Either direction of crossing is found by both signs of the difference
+    let mut sun_conjunctions =
+        find_crossings(jd_from, jd_to, 1.0, |jd| longitude(jd) - sun_longitude(jd));
+    sun_conjunctions.extend(find_crossings(jd_from, jd_to, 1.0, |jd| {
+        sun_longitude(jd) - longitude(jd)
+    }));
+    let mut events: Vec<_> = sun_conjunctions
+        .into_iter()
+        .map(|jd| {
+            let kind = match (planet.is_inferior(), position(planet, jd).distance < 1.0) {
+                (true, true) => PlanetEventKind::InferiorConjunction,
+                (true, false) => PlanetEventKind::SuperiorConjunction,
+                (false, _) => PlanetEventKind::Conjunction,
+            };
+            event(kind, jd)
+        })
+        .collect();
+
+    if !planet.is_inferior() {
+        let oppositions = find_crossings(jd_from, jd_to, 1.0, |jd| {
+            sun_longitude(jd) + 180.0 - longitude(jd)
+        });
+        events.extend(
+            oppositions
+                .into_iter()
+                .map(|jd| event(PlanetEventKind::Opposition, jd)),
+        );
+    }
+
+    let moon_conjunctions =
+        find_crossings(jd_from, jd_to, 0.5, |jd| moon_longitude(jd) - longitude(jd));
+    events.extend(
+        moon_conjunctions
+            .into_iter()
+            .map(|jd| event(PlanetEventKind::MoonConjunction, jd)),
+    );
+
+    events.sort_by(|a, b| a.jd.total_cmp(&b.jd));
+    events
+}
@@ -0,0 +1,254 @@
+//! Contains rising and setting calculations.
+
+use serde::Deserialize;
+
+use crate::astro::{
+    angle::{normalize_signed, signed_difference},
+    coords::ecliptic_to_equatorial,
+    julian::local_apparent_sidereal_time,
+    longitude::jcg78::sun_longitude,
+    math::{acos, cos, sin, tan},
+    moon,
+};
+
+/// Mean semi-diameter of the sun (16') in degree.
+const SUN_SEMI_DIAMETER: f64 = 16.0 / 60.0;
+
+/// Standard atmospheric pressure for refraction in hectopascal.
+pub const STANDARD_PRESSURE: f64 = 1010.0;
+
+/// Standard air temperature for refraction in degree Celsius.
+pub const STANDARD_TEMPERATURE: f64 = 10.0;
+
+/// Mean length of lunar day in solar days.
+const LUNAR_DAY: f64 = 1.035;
+
+/// Sidereal degrees per solar day.
+const SIDEREAL_RATE: f64 = 360.98564736629;
+
+/// Represents an observer on the earth.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Observer {
+    /// Latitude in degree, positive for north.
+    pub latitude: f64,
+
+    /// Longitude in degree, positive for east.
+    pub longitude: f64,
+}
+
+/// Represents a model of the atmospheric refraction.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Refraction {
+    /// No refraction, for the geometric rising and setting.
+    Geometric,
+
+    /// Bennett's formula, scaled by the pressure in hectopascal and the temperature in degree Celsius.
+    Atmospheric { pressure: f64, temperature: f64 },
+}
+
+impl Default for Refraction {
+    fn default() -> Refraction {
+        Refraction::Atmospheric {
+            pressure: STANDARD_PRESSURE,
+            temperature: STANDARD_TEMPERATURE,
+        }
+    }
+}
+
+impl Refraction {
+    /// Calculates the refraction in degree for the apparent altitude in degree.
+    /// It is about 34' on the horizon in the standard atmosphere.
+    pub fn at_apparent_altitude(self, altitude: f64) -> f64 {
+        match self {
+            Refraction::Geometric => 0.0,
+            Refraction::Atmospheric {
+                pressure,
+                temperature,
+            } => {
+                let arcmin = 1.0 / tan((altitude + 7.31 / (altitude + 4.4)).to_radians());
+                let scale = pressure / STANDARD_PRESSURE * (273.0 + STANDARD_TEMPERATURE)
+                    / (273.0 + temperature);
+                arcmin * scale / 60.0
+            }
+        }
+    }
+}
+
+/// Represents a selection of refraction models in queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RefractionKind {
+    /// Bennett's formula.
+    #[default]
+    Atmospheric,
+
+    /// No refraction.
+    Geometric,
+}
+
+impl RefractionKind {
+    /// Makes the refraction model, where missing conditions are the standard ones.
+    pub fn with(self, pressure: Option<f64>, temperature: Option<f64>) -> Refraction {
+        match self {
+            RefractionKind::Atmospheric => Refraction::Atmospheric {
+                pressure: pressure.unwrap_or(STANDARD_PRESSURE),
+                temperature: temperature.unwrap_or(STANDARD_TEMPERATURE),
+            },
+            RefractionKind::Geometric => Refraction::Geometric,
+        }
+    }
+}
+
+/// Represents rising, transit and setting instants in Julian Date.
+/// Each is `None` if the body does not cross the altitude or the meridian on the day.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiseSet {
+    pub rise: Option<f64>,
+    pub transit: Option<f64>,
+    pub set: Option<f64>,
+}
+
+/// Represents a crossing of the altitude.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Crossing {
+    Rise,
+    Set,
+}
+
+/// Calculates sunrise and sunset around the local noon nearest to the Julian Date,
+/// when the upper limb of the sun is on the horizon.
+pub fn sun_riseset(jd: f64, observer: &Observer, refraction: Refraction) -> RiseSet {
+    let sun_position = |jd| ecliptic_to_equatorial(jd, sun_longitude(jd), 0.0);
+    let altitude = -refraction.at_apparent_altitude(0.0) - SUN_SEMI_DIAMETER;
+    riseset(jd, observer, sun_position, altitude)
+}
+
+/// Represents a kind of twilight.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Twilight {
+    Civil,
+    Nautical,
+    Astronomical,
+}
+
+impl Twilight {
+    /// All kinds of twilight from the brightest.
+    pub const ALL: [Twilight; 3] = [Twilight::Civil, Twilight::Nautical, Twilight::Astronomical];
+
+    /// Gets the depression angle of the sun's center in degree.
+    pub fn depression(self) -> f64 {
+        match self {
+            Twilight::Civil => 6.0,
+            Twilight::Nautical => 12.0,
+            Twilight::Astronomical => 18.0,
+        }
+    }
+
+    /// Gets the name in lower case.
+    pub fn name(self) -> &'static str {
+        match self {
+            Twilight::Civil => "civil",
+            Twilight::Nautical => "nautical",
+            Twilight::Astronomical => "astronomical",
+        }
+    }
+}
+
+/// Calculates the start of dawn and the end of dusk around the local noon nearest to the Julian Date,
+/// when the sun's center is at the depression angle in degree below the horizon.
+/// Rising is the dawn and setting is the dusk.
+pub fn twilight(jd: f64, observer: &Observer, depression: f64) -> RiseSet {
+    let sun_position = |jd| ecliptic_to_equatorial(jd, sun_longitude(jd), 0.0);
+    riseset(jd, observer, sun_position, -depression)
+}
+
+/// Calculates moonrise and moonset in the day starting at the Julian Date,
+/// when the upper limb of the moon is on the horizon.
+pub fn moon_riseset(jd_day_start: f64, observer: &Observer, refraction: Refraction) -> RiseSet {
+    // Topocentric position has the parallax, so only the refraction and the semi-diameter remain
+    let moon_position = |jd| moon::topocentric_position(jd, observer);
+    let altitude = -refraction.at_apparent_altitude(0.0) - moon::semi_diameter(jd_day_start + 0.5);
+    let jd_day_end = jd_day_start + 1.0;
+
+    // The moon transits about 50 minutes later every day, so transits around the day are checked
+    let candidates: Vec<_> = [-1.0, 0.0, 1.0]
+        .iter()
+        .map(|k| {
+            let jd = jd_day_start + 0.5 + k * LUNAR_DAY;
+            riseset(jd, observer, moon_position, altitude)
+        })
+        .collect();
+    let in_day = |jd: &f64| (jd_day_start..jd_day_end).contains(jd);
+    RiseSet {
+        rise: candidates.iter().filter_map(|c| c.rise).find(in_day),
+        transit: candidates.iter().filter_map(|c| c.transit).find(in_day),
+        set: candidates.iter().filter_map(|c| c.set).find(in_day),
+    }
+}
+
+/// Calculates rising and setting of the body with equatorial coordinates.
+fn riseset(
+    jd: f64,
+    observer: &Observer,
+    position: impl Fn(f64) -> (f64, f64),
+    altitude: f64,
+) -> RiseSet {
+    let transit = find_transit(jd, observer, &position);
+    RiseSet {
+        rise: find_crossing(transit, observer, &position, altitude, Crossing::Rise),
+        transit: Some(transit),
+        set: find_crossing(transit, observer, &position, altitude, Crossing::Set),
+    }
+}
+
+/// Calculates local hour angle of the right ascension in (-180, 180] degree.
+fn hour_angle(jd: f64, observer: &Observer, right_ascension: f64) -> f64 {
+    let lst = local_apparent_sidereal_time(jd, observer.longitude);
+    signed_difference(lst, right_ascension)
+}
+
+/// Finds the upper transit near the Julian Date.
+fn find_transit(jd: f64, observer: &Observer, position: impl Fn(f64) -> (f64, f64)) -> f64 {
+    let mut jd = jd;
+    for _ in 0..10 {
+        let (right_ascension, _) = position(jd);
+        let delta_t = hour_angle(jd, observer, right_ascension) / SIDEREAL_RATE;
+        jd -= delta_t;
+        if delta_t.abs() < 1.0 / 86400.0 {
+            break;
+        }
+    }
+    jd
+}
+
+/// Finds the instant when the body crosses the altitude before or after the transit.
+fn find_crossing(
+    jd_transit: f64,
+    observer: &Observer,
+    position: impl Fn(f64) -> (f64, f64),
+    altitude: f64,
+    crossing: Crossing,
+) -> Option<f64> {
+    let mut jd = jd_transit;
+    for _ in 0..10 {
+        let (right_ascension, declination) = position(jd);
+        let (phi, delta) = (observer.latitude.to_radians(), declination.to_radians());
+        let cos_h0 = (sin(altitude.to_radians()) - sin(phi) * sin(delta)) / (cos(phi) * cos(delta));
+        if !(-1.0..=1.0).contains(&cos_h0) {
+            return None;
+        }
+
+        let h0 = acos(cos_h0).to_degrees();
+        let target = match crossing {
+            Crossing::Rise => -h0,
+            Crossing::Set => h0,
+        };
+        let delta_h = hour_angle(jd, observer, right_ascension) - target;
+        let delta_t = normalize_signed(delta_h);
+        jd -= delta_t / SIDEREAL_RATE;
+        if delta_t.abs() / SIDEREAL_RATE < 1.0 / 86400.0 {
+            break;
+        }
+    }
+    Some(jd)
+}
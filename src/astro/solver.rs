@@ -0,0 +1,232 @@
+//! Contains root finding for event instants.
+
+use std::sync::OnceLock;
+
+use anyhow::{bail, Result};
+
+use crate::error::ConversionError;
+
+/// Tolerance of found instants in days, about a millisecond.
+pub const TOLERANCE: f64 = 1e-8;
+
+/// Maximum tolerance in seconds which can be requested.
+/// Brackets of solvers are days wide, so coarser tolerances would not converge to the events.
+pub const MAX_TOLERANCE_SECONDS: f64 = 3600.0;
+
+/// Tolerance in days configured at the startup, which replaces `TOLERANCE`.
+static CONFIGURED_TOLERANCE: OnceLock<f64> = OnceLock::new();
+
+/// Maximum count of iterations.
+const MAX_ITERATIONS: usize = 100;
+
+/// Step in days to take slopes of approximations.
+const SLOPE_STEP: f64 = 1e-3;
+
+/// Converts the tolerance in seconds into days, checking that it is supported.
+pub fn tolerance_from_seconds(seconds: f64) -> Result<f64> {
+    if !(seconds > 0.0 && seconds <= MAX_TOLERANCE_SECONDS) {
+        return Err(ConversionError::UnsupportedOptions {
+            requested: format!("Solver tolerance of {} seconds", seconds),
+        }
+        .into());
+    }
+    Ok(seconds / 86400.0)
+}
+
+/// Configures the default tolerance in seconds, which can be done only once.
+pub fn configure_tolerance(seconds: f64) -> Result<()> {
+    let tolerance = tolerance_from_seconds(seconds)?;
+    if CONFIGURED_TOLERANCE.set(tolerance).is_err() {
+        bail!("Solver tolerance is already configured");
+    }
+    Ok(())
+}
+
+/// Gets the default tolerance in days, which is `TOLERANCE` unless configured.
+pub fn default_tolerance() -> f64 {
+    CONFIGURED_TOLERANCE.get().copied().unwrap_or(TOLERANCE)
+}
+
+/// Finds a root of the function in the bracket by Brent's method, to the default tolerance.
+/// The function should have opposite signs at both ends.
+pub fn find_root(low: f64, high: f64, function: impl Fn(f64) -> f64) -> Result<f64> {
+    find_root_within(low, high, default_tolerance(), function)
+}
+
+/// Represents a found root with the state of convergence.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RootDiagnostics {
+    /// The found root.
+    pub root: f64,
+
+    /// Count of iterations, excluding evaluations at the ends of the bracket.
+    pub iterations: usize,
+
+    /// Value of the function at the root.
+    pub residual: f64,
+}
+
+/// Finds a root of the function in the bracket by Brent's method, to the tolerance in days.
+pub fn find_root_within(
+    low: f64,
+    high: f64,
+    tolerance: f64,
+    function: impl Fn(f64) -> f64,
+) -> Result<f64> {
+    Ok(diagnose_root_within(low, high, tolerance, function)?.root)
+}
+
+/// Finds a root of the function in the bracket by Brent's method, reporting the convergence.
+pub fn diagnose_root_within(
+    low: f64,
+    high: f64,
+    tolerance: f64,
+    function: impl Fn(f64) -> f64,
+) -> Result<RootDiagnostics> {
+    let required = tolerance;
+    let (mut a, mut b) = (low, high);
+    let (mut fa, mut fb) = (function(a), function(b));
+    let found = |root, iterations, residual| RootDiagnostics {
+        root,
+        iterations,
+        residual,
+    };
+    if fa == 0.0 {
+        return Ok(found(a, 0, fa));
+    } else if fb == 0.0 {
+        return Ok(found(b, 0, fb));
+    } else if fa.signum() == fb.signum() {
+        bail!("Root is not bracketed in [{}, {}]", low, high);
+    }
+
+    let (mut c, mut fc) = (b, fb);
+    let (mut d, mut e) = (b - a, b - a);
+    for iterations in 0..MAX_ITERATIONS {
+        if fb.signum() == fc.signum() {
+            c = a;
+            fc = fa;
+            d = b - a;
+            e = d;
+        }
+        if fc.abs() < fb.abs() {
+            a = b;
+            b = c;
+            c = a;
+            fa = fb;
+            fb = fc;
+            fc = fa;
+        }
+
+        let tolerance = 2.0 * f64::EPSILON * b.abs() + 0.5 * required;
+        let middle = 0.5 * (c - b);
+        if middle.abs() <= tolerance || fb == 0.0 {
+            return Ok(found(b, iterations, fb));
+        }
+
+        if e.abs() >= tolerance && fa.abs() > fb.abs() {
+            // Inverse quadratic interpolation, or secant method
+            let s = fb / fa;
+            let (mut p, mut q) = if a == c {
+                (2.0 * middle * s, 1.0 - s)
+            } else {
+                let (q, r) = (fa / fc, fb / fc);
+                (
+                    s * (2.0 * middle * q * (q - r) - (b - a) * (r - 1.0)),
+                    (q - 1.0) * (r - 1.0) * (s - 1.0),
+                )
+            };
+            if p > 0.0 {
+                q = -q;
+            }
+            p = p.abs();
+
+            let min1 = 3.0 * middle * q - (tolerance * q).abs();
+            let min2 = (e * q).abs();
+            if 2.0 * p < min1.min(min2) {
+                e = d;
+                d = p / q;
+            } else {
+                d = middle;
+                e = d;
+            }
+        } else {
+            d = middle;
+            e = d;
+        }
+
+        a = b;
+        fa = fb;
+        b += if d.abs() > tolerance {
+            d
+        } else {
+            tolerance.copysign(middle)
+        };
+        fb = function(b);
+    }
+
+    bail!("Root finding did not converge in [{}, {}]", low, high);
+}
+
+/// Finds a root of the function in the bracket, on the approximation of it first.
+/// The root of the approximation is corrected by a Newton step on the function with the slope of
+/// the approximation, and accepted if the function changes its sign within the tolerance around it.
+/// Otherwise the function is solved in the whole bracket.
+pub fn diagnose_root_refined(
+    low: f64,
+    high: f64,
+    tolerance: f64,
+    approximation: impl Fn(f64) -> f64,
+    function: impl Fn(f64) -> f64,
+) -> Result<RootDiagnostics> {
+    if let Ok(approximate) = diagnose_root_within(low, high, tolerance, &approximation) {
+        let (before, after) = (
+            approximation(approximate.root - SLOPE_STEP),
+            approximation(approximate.root + SLOPE_STEP),
+        );
+        let slope = (after - before) / (2.0 * SLOPE_STEP);
+        let root = approximate.root - function(approximate.root) / slope;
+
+        let (a, b) = (root - 0.5 * tolerance, root + 0.5 * tolerance);
+        if low <= a && b <= high {
+            let (fa, fb) = (function(a), function(b));
+            if fa.signum() != fb.signum() || fa == 0.0 || fb == 0.0 {
+                let (root, residual) = if fa.abs() <= fb.abs() {
+                    (a, fa)
+                } else {
+                    (b, fb)
+                };
+                return Ok(RootDiagnostics {
+                    root,
+                    iterations: 1,
+                    residual,
+                });
+            }
+        }
+    }
+    diagnose_root_within(low, high, tolerance, function)
+}
+
+/// Finds a minimum of the function in the bracket by golden section search, to the tolerance in days.
+/// The function should be unimodal in the bracket.
+pub fn find_minimum(
+    low: f64,
+    high: f64,
+    tolerance: f64,
+    function: impl Fn(f64) -> f64,
+) -> Result<f64> {
+    let ratio = (5.0f64.sqrt() - 1.0) / 2.0;
+    let (mut low, mut high) = (low, high);
+    for _ in 0..MAX_ITERATIONS {
+        if high - low <= tolerance {
+            return Ok((low + high) / 2.0);
+        }
+        let left = high - ratio * (high - low);
+        let right = low + ratio * (high - low);
+        if function(left) < function(right) {
+            high = right;
+        } else {
+            low = left;
+        }
+    }
+    bail!("Minimum finding did not converge in [{}, {}]", low, high);
+}
@@ -0,0 +1,122 @@
+//! Contains solar time and position calculations.
+
+use anyhow::Result;
+use chrono::prelude::*;
+
+use crate::{
+    astro::{
+        angle::signed_difference,
+        coords::{ecliptic_to_equatorial, equatorial_to_horizontal},
+        julian::{julian_century, local_apparent_sidereal_time, to_julian_date},
+        longitude::{jcg78::sun_longitude, vsop87::sun_distance},
+        math::cos,
+        riseset::Observer,
+        solver::find_minimum,
+    },
+    error::ConversionError,
+};
+
+/// Equatorial horizontal parallax of the sun in degree.
+const SUN_PARALLAX: f64 = 8.794 / 3600.0;
+
+/// Represents an apsis of the orbit of the earth.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OrbitApsis {
+    /// The nearest point to the sun, 近日点.
+    Perihelion,
+
+    /// The farthest point from the sun, 遠日点.
+    Aphelion,
+}
+
+impl OrbitApsis {
+    /// Gets Japanese name.
+    pub fn to_japanese(self) -> &'static str {
+        match self {
+            OrbitApsis::Perihelion => "近日点",
+            OrbitApsis::Aphelion => "遠日点",
+        }
+    }
+
+    /// Gets English name.
+    pub fn to_english(self) -> &'static str {
+        match self {
+            OrbitApsis::Perihelion => "perihelion",
+            OrbitApsis::Aphelion => "aphelion",
+        }
+    }
+}
+
+/// Represents a position of the sun.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SunPosition {
+    /// Right ascension in degree.
+    pub right_ascension: f64,
+
+    /// Declination in degree.
+    pub declination: f64,
+
+    /// Topocentric altitude in degree, without refraction.
+    pub altitude: f64,
+
+    /// Azimuth in degree, measured from north to east.
+    pub azimuth: f64,
+}
+
+/// Calculates the equation of time, apparent solar time minus mean solar time, in minutes.
+pub fn equation_of_time(jd: f64) -> f64 {
+    let jc = julian_century(jd);
+    let mean_longitude = 280.4665 + 36000.7698 * jc;
+    let (right_ascension, _) = ecliptic_to_equatorial(jd, sun_longitude(jd), 0.0);
+
+    // Aberration of 20.5" is included in the apparent longitude
+    let delta = signed_difference(mean_longitude - 0.0057183, right_ascension);
+    delta * 4.0
+}
+
+/// Calculates the true solar noon on the date at the longitude in degree (positive for east).
+/// The date is given as the Julian Date of its UTC midnight.
+pub fn solar_noon(jd_midnight: f64, longitude: f64) -> f64 {
+    let jd_mean_noon = jd_midnight + 0.5 - longitude / 360.0;
+    let mut jd = jd_mean_noon;
+    for _ in 0..3 {
+        jd = jd_mean_noon - equation_of_time(jd) / 1440.0;
+    }
+    jd
+}
+
+/// Calculates the position of the sun seen from the observer.
+pub fn position(jd: f64, observer: &Observer) -> SunPosition {
+    let (right_ascension, declination) = ecliptic_to_equatorial(jd, sun_longitude(jd), 0.0);
+    let hour_angle = local_apparent_sidereal_time(jd, observer.longitude) - right_ascension;
+    let (geocentric_altitude, azimuth) =
+        equatorial_to_horizontal(hour_angle, declination, observer.latitude);
+
+    SunPosition {
+        right_ascension,
+        declination,
+        altitude: geocentric_altitude - SUN_PARALLAX * cos(geocentric_altitude.to_radians()),
+        azimuth,
+    }
+}
+
+/// Calculates the distance of the sun in astronomical unit.
+pub fn distance(jd: f64) -> f64 {
+    sun_distance(jd)
+}
+
+/// Finds the apsis of the kind in the Gregorian year, with Julian Date.
+/// The distance is flat around the apsides, so the truncated series gives the instant within a few hours.
+pub fn apsis_in_year(year: i32, apsis: OrbitApsis) -> Result<f64> {
+    // Perihelion falls in early January and aphelion in early July, both within a week.
+    // The annual term dominates the lunar one there, so the distance has a single extremum.
+    let (month, sign) = match apsis {
+        OrbitApsis::Perihelion => (1, 1.0),
+        OrbitApsis::Aphelion => (7, -1.0),
+    };
+    let jd_center = to_julian_date(&Utc.ymd(year, month, 1).and_hms(0, 0, 0)) + 3.5;
+    find_minimum(jd_center - 10.0, jd_center + 10.0, 1.0 / 1440.0, |jd| {
+        sign * distance(jd)
+    })
+    .map_err(|_| ConversionError::SolverDidNotConverge { jd: jd_center }.into())
+}
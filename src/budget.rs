@@ -0,0 +1,70 @@
+//! Contains the compute budget of conversions, which bounds the time spent in the solvers.
+//!
+//! The deadline is kept per thread, so it is set on the thread handling the request
+//! and passed to the threads spawned for it. Without a deadline, calculations are unbounded.
+
+use std::{
+    cell::Cell,
+    time::{Duration, Instant},
+};
+
+use anyhow::Result;
+
+use crate::error::ConversionError;
+
+thread_local! {
+    /// The deadline of the calculation running on this thread.
+    static DEADLINE: Cell<Option<Deadline>> = const { Cell::new(None) };
+}
+
+/// Represents the instant by which the calculation should finish.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Deadline {
+    /// The instant of the deadline.
+    at: Instant,
+
+    /// The budget the deadline was set with, for reports.
+    budget: Duration,
+}
+
+/// Restores the previous deadline when the calculation ends, even by panic.
+struct Restore(Option<Deadline>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        DEADLINE.with(|d| d.set(self.0));
+    }
+}
+
+/// Runs the calculation within the budget from now.
+pub fn with_budget<T>(budget: Duration, calculation: impl FnOnce() -> T) -> T {
+    let deadline = Deadline {
+        at: Instant::now() + budget,
+        budget,
+    };
+    with_deadline(Some(deadline), calculation)
+}
+
+/// Runs the calculation with the deadline, which is usually taken from another thread.
+pub fn with_deadline<T>(deadline: Option<Deadline>, calculation: impl FnOnce() -> T) -> T {
+    let _restore = Restore(DEADLINE.with(|d| d.replace(deadline)));
+    calculation()
+}
+
+/// Gets the deadline of the calculation running on this thread.
+pub fn deadline() -> Option<Deadline> {
+    DEADLINE.with(|d| d.get())
+}
+
+/// Checks that the calculation running on this thread is still within the budget.
+pub fn check() -> Result<()> {
+    match deadline() {
+        Some(deadline) if Instant::now() > deadline.at => {
+            Err(ConversionError::ComputeBudgetExceeded {
+                budget_ms: deadline.budget.as_millis() as u64,
+            }
+            .into())
+        }
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,60 @@
+//! Contains counters of the caches, which are reported for capacity tuning.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// Counts lookups and evictions of a cache.
+/// Counters are only for reports, so they are updated without synchronizing other memory.
+#[derive(Debug, Default)]
+pub struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    evictions: AtomicU64,
+}
+
+/// Represents counters of a cache at a moment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups found in the cache.
+    pub hits: u64,
+
+    /// Lookups not found in the cache.
+    pub misses: u64,
+
+    /// Entries evicted to bound the size.
+    pub evictions: u64,
+
+    /// Number of entries in the cache.
+    pub entries: usize,
+}
+
+impl CacheCounters {
+    /// Constructs with zero counts.
+    pub const fn new() -> CacheCounters {
+        CacheCounters {
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+            evictions: AtomicU64::new(0),
+        }
+    }
+
+    /// Counts a lookup by whether it is found.
+    pub fn record(&self, found: bool) {
+        let counter = if found { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Counts evicted entries.
+    pub fn record_evictions(&self, count: u64) {
+        self.evictions.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Takes the counts with the number of entries.
+    pub fn stats(&self, entries: usize) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            evictions: self.evictions.load(Ordering::Relaxed),
+            entries,
+        }
+    }
+}
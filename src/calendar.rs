@@ -0,0 +1,402 @@
+//! Contains calendar system abstraction for lunisolar calendars.
+
+use anyhow::Result;
+use chrono::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    astro::{
+        julian::julian_day_of, longitude::jcg78::ensure_supported_date, moon::SYNODIC_MONTH,
+        sun::equation_of_time,
+    },
+    error::ConversionError,
+    historical::{HORYAKU, JOKYO, KANSEI, SENMYO},
+    lunisolar::{KOREAN, VIETNAMESE},
+    sekki::Sekki,
+    tempo::{calculate_leading_saku, calculate_sakus, TempoMonth, TEMPO},
+};
+
+/// Longitude of the meridian of JST in degree.
+pub const JST_MERIDIAN: f64 = 135.0;
+
+/// Offset of JST from UTC in days, which is the reference meridian of requested dates.
+pub const JST_OFFSET: f64 = JST_MERIDIAN / 360.0;
+
+/// Longest span in days from a date to the ends of the 11th months around it,
+/// which are 12 or 13 months apart.
+pub const SUI_SPAN: f64 = 15.0 * SYNODIC_MONTH;
+
+/// Represents a lunisolar calendar system.
+/// Sakus are calculated by the shared astronomical core,
+/// and each calendar decides sekkis, month starts and naming of months.
+pub trait CalendarSystem {
+    /// Gets the offset of the reference meridian from UTC in days.
+    fn meridian_offset(&self) -> f64;
+
+    /// Calculates chukis whose instants are in the range.
+    fn chukis(&self, jd_from: f64, jd_to: f64) -> Result<Vec<(f64, Sekki)>>;
+
+    /// Gets the local instant of the Julian Date, whose days start at local midnights.
+    fn local_instant(&self, jd: f64) -> f64 {
+        local_instant(jd, self.meridian_offset())
+    }
+
+    /// Gets the local date (as UTC midnight JD) of the instant.
+    fn local_day(&self, jd: f64) -> f64 {
+        local_day(self.local_instant(jd), 0.0)
+    }
+
+    /// Gets the local date (as UTC midnight JD) on which the month starting at the saku begins.
+    fn month_start_day(&self, jd_saku: f64) -> f64 {
+        self.local_day(jd_saku)
+    }
+
+    /// Names months which start at each day.
+    /// A month is named by the chuki (中気) it contains, compared by the local date of the chuki,
+    /// and the month containing toji is the 11th month.
+    ///
+    /// Where the 11th months are 13 months apart, the first month without chuki is the leap month.
+    /// Other months follow in order, so a month containing two chukis does not skip the next name
+    /// and a month without chuki is not leap in a year of 12 months.
+    /// Months before the first toji or after the last one are left unnamed as month 0.
+    fn name_months(&self, month_days: &[f64], chukis: &[(f64, Sekki)]) -> Vec<TempoMonth> {
+        let chuki_days: Vec<_> = chukis
+            .iter()
+            .map(|(jd, sekki)| (self.local_day(*jd), *sekki))
+            .collect();
+        let contained: Vec<Vec<Sekki>> = month_days
+            .windows(2)
+            .map(|days| {
+                chuki_days
+                    .iter()
+                    .filter(|(day, _)| (days[0]..days[1]).contains(day))
+                    .map(|(_, sekki)| *sekki)
+                    .collect()
+            })
+            .collect();
+
+        let tojis: Vec<_> = (0..contained.len())
+            .filter(|&i| contained[i].contains(&Sekki::Toji))
+            .collect();
+        let mut names = vec![(0, false); contained.len()];
+        for pair in tojis.windows(2) {
+            let (first, last) = (pair[0], pair[1]);
+            let mut leap_remaining = last - first == 13;
+            let mut month = 11;
+            names[first] = (month, false);
+            for i in (first + 1)..last {
+                if leap_remaining && contained[i].is_empty() {
+                    names[i] = (month, true);
+                    leap_remaining = false;
+                } else {
+                    month = month % 12 + 1;
+                    names[i] = (month, false);
+                }
+            }
+            names[last] = (11, false);
+        }
+
+        month_days
+            .windows(2)
+            .zip(names)
+            .map(|(days, (month, leap_month))| TempoMonth {
+                leap_month,
+                month,
+                jd_start: days[0],
+                jd_end: days[1],
+            })
+            .collect()
+    }
+
+    /// Calculates months between the 11th months around the date.
+    fn calculate_months(&self, jd: f64) -> Result<Vec<TempoMonth>> {
+        let sakus = calculate_sakus(jd - SUI_SPAN, jd + SUI_SPAN)?;
+
+        let month_days: Vec<_> = sakus.iter().map(|s| self.month_start_day(*s)).collect();
+        let chukis = self.chukis(sakus[0] - 1.0, sakus[sakus.len() - 1] + 1.0)?;
+        let mut months = self.name_months(&month_days, &chukis);
+        months.retain(|m| m.month != 0);
+        Ok(months)
+    }
+
+    /// Calculates the instants of sakus which begin and end the month.
+    fn saku_instants(&self, month: &TempoMonth) -> Result<(f64, f64)> {
+        let jd_start = calculate_leading_saku(month.jd_start + 1.0 - self.meridian_offset())?;
+        let jd_end = calculate_leading_saku(month.jd_end + 1.0 - self.meridian_offset())?;
+        Ok((jd_start, jd_end))
+    }
+}
+
+/// Represents a month with the instants of sakus on its boundaries.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonthBoundaries {
+    /// The month containing the date.
+    pub month: TempoMonth,
+
+    /// Instant of the saku which starts this month.
+    pub jd_saku_start: f64,
+
+    /// Instant of the saku which starts the next month.
+    pub jd_saku_end: f64,
+
+    /// Offset of the reference meridian from UTC in days.
+    pub meridian_offset: f64,
+}
+
+/// Calculates the month containing the local date and its boundary instants.
+pub fn month_boundaries(date: NaiveDate, calendar: Calendar) -> Result<MonthBoundaries> {
+    ensure_supported_date(date)?;
+    let jd_date = julian_day_of(date);
+    let system = calendar.system_at(jd_date - JST_OFFSET);
+    let jd = jd_date - system.meridian_offset();
+    let months = system.calculate_months(jd)?;
+    let month = *months
+        .iter()
+        .rev()
+        .find(|m| jd_date >= m.jd_start)
+        .expect("Should be found");
+    let (jd_saku_start, jd_saku_end) = system.saku_instants(&month)?;
+
+    Ok(MonthBoundaries {
+        month,
+        jd_saku_start,
+        jd_saku_end,
+        meridian_offset: system.meridian_offset(),
+    })
+}
+
+/// Represents the definition of the day boundary, which decides the dates of sakus and chukis.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum DayBoundary {
+    /// Midnight at the reference meridian of the calendar, which is JST for tempo calendar.
+    #[default]
+    Standard,
+
+    /// Mean midnight at the longitude in degree (positive for east).
+    MeanMidnight(f64),
+
+    /// True solar midnight at the longitude in degree (positive for east),
+    /// when the apparent sun crosses the lower meridian.
+    SolarMidnight(f64),
+}
+
+impl DayBoundary {
+    /// Gets the local instant of the Julian Date, whose days start at the boundaries,
+    /// where the standard boundary is at the meridian offset in days.
+    pub fn local_instant(self, jd: f64, meridian_offset: f64) -> f64 {
+        match self {
+            DayBoundary::Standard => local_instant(jd, meridian_offset),
+            DayBoundary::MeanMidnight(longitude) => local_instant(jd, longitude / 360.0),
+            DayBoundary::SolarMidnight(longitude) => {
+                local_instant(jd, longitude / 360.0 + equation_of_time(jd) / 1440.0)
+            }
+        }
+    }
+}
+
+/// Represents a selection of day boundary definitions in queries.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DayBoundaryKind {
+    /// Midnight at the reference meridian of the calendar.
+    #[default]
+    Standard,
+
+    /// Mean midnight at the longitude.
+    Mean,
+
+    /// True solar midnight at the longitude.
+    Solar,
+}
+
+impl DayBoundaryKind {
+    /// Makes the day boundary at the longitude in degree.
+    pub fn at(self, longitude: f64) -> DayBoundary {
+        match self {
+            DayBoundaryKind::Standard => DayBoundary::Standard,
+            DayBoundaryKind::Mean => DayBoundary::MeanMidnight(longitude),
+            DayBoundaryKind::Solar => DayBoundary::SolarMidnight(longitude),
+        }
+    }
+}
+
+/// Represents the method to place chukis, which name the months.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TermMethod {
+    /// True terms (定気法), when the sun reaches each 30 degrees of longitude.
+    #[default]
+    True,
+
+    /// Mean terms (平気法), which divide each year from a true toji into 12 equal terms.
+    Mean,
+}
+
+/// Represents a selection of calendar systems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Calendar {
+    /// Tempo calendar (天保暦), used since 1844.
+    #[default]
+    Tempo,
+
+    /// Senmyo calendar (宣明暦), used from 862 to 1684.
+    Senmyo,
+
+    /// Jokyo calendar (貞享暦), used from 1685 to 1754.
+    Jokyo,
+
+    /// Horyaku calendar (宝暦暦), used from 1755 to 1797.
+    Horyaku,
+
+    /// Kansei calendar (寛政暦), used from 1798 to 1843.
+    Kansei,
+
+    /// Korean calendar in UTC+9.
+    Korean,
+
+    /// Vietnamese calendar in UTC+7.
+    Vietnamese,
+
+    /// The calendar which was in use at the date.
+    Auto,
+}
+
+/// Represents the handling of dates before the adoption of the selected calendar.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum HistoricalPolicy {
+    /// Rejects the dates.
+    Error,
+
+    /// Applies the rules of the calendar anyway, flagging the results as proleptic.
+    #[default]
+    Proleptic,
+
+    /// Uses the calendar which was in use at each date instead.
+    Dispatch,
+}
+
+/// First days of calendars in Gregory date, which are 1/1 of each first year.
+const CALENDAR_EPOCHS: &[((i32, u32, u32), Calendar)] = &[
+    ((1844, 2, 18), Calendar::Tempo),
+    ((1798, 2, 16), Calendar::Kansei),
+    ((1755, 2, 11), Calendar::Horyaku),
+    ((1685, 2, 4), Calendar::Jokyo),
+];
+
+impl Calendar {
+    /// Gets the calendar which was in use at the Julian Date.
+    /// Dates before Jokyo calendar are resolved to Senmyo calendar.
+    pub fn in_use_at(jd: f64) -> Calendar {
+        let jd_date = local_day(jd, JST_OFFSET);
+        CALENDAR_EPOCHS
+            .iter()
+            .find(|&&((y, m, d), _)| jd_date >= julian_day_of(NaiveDate::from_ymd(y, m, d)))
+            .map(|(_, calendar)| *calendar)
+            .unwrap_or(Calendar::Senmyo)
+    }
+
+    /// Gets the first day of the calendar in Gregory date, if its adoption is in the supported range.
+    pub fn adoption_date(self) -> Option<NaiveDate> {
+        CALENDAR_EPOCHS
+            .iter()
+            .find(|(_, calendar)| *calendar == self)
+            .map(|&((y, m, d), _)| NaiveDate::from_ymd(y, m, d))
+    }
+
+    /// Resolves the calendar for JST dates from the first one by the policy.
+    /// Returns the calendar to use and whether it is applied before its adoption.
+    pub fn resolve(self, first: NaiveDate, policy: HistoricalPolicy) -> Result<(Calendar, bool)> {
+        let adoption = match self.adoption_date() {
+            Some(adoption) if first < adoption => adoption,
+            _ => return Ok((self, false)),
+        };
+        match policy {
+            HistoricalPolicy::Error => Err(ConversionError::BeforeAdoption {
+                requested: first,
+                adoption,
+            }
+            .into()),
+            HistoricalPolicy::Proleptic => Ok((self, true)),
+            HistoricalPolicy::Dispatch => Ok((Calendar::Auto, false)),
+        }
+    }
+
+    /// Whether conversions in the calendar from the JST date are approximate.
+    /// Historical calendars are approximated by modern true new moons and their mean chukis,
+    /// not by their own tables of the sun and the moon.
+    pub fn is_approximate_from(self, first: NaiveDate) -> bool {
+        match self {
+            Calendar::Senmyo | Calendar::Jokyo | Calendar::Horyaku | Calendar::Kansei => true,
+            Calendar::Tempo | Calendar::Korean | Calendar::Vietnamese => false,
+            Calendar::Auto => Calendar::Tempo
+                .adoption_date()
+                .is_some_and(|adoption| first < adoption),
+        }
+    }
+
+    /// Gets the calendar system for the Julian Date.
+    pub fn system_at(self, jd: f64) -> &'static dyn CalendarSystem {
+        match self {
+            Calendar::Tempo => &TEMPO,
+            Calendar::Senmyo => &SENMYO,
+            Calendar::Jokyo => &JOKYO,
+            Calendar::Horyaku => &HORYAKU,
+            Calendar::Kansei => &KANSEI,
+            Calendar::Korean => &KOREAN,
+            Calendar::Vietnamese => &VIETNAMESE,
+            Calendar::Auto => Calendar::in_use_at(jd).system_at(jd),
+        }
+    }
+}
+
+/// Gets the time zone of the reference meridian.
+pub fn meridian_timezone(meridian_offset: f64) -> FixedOffset {
+    FixedOffset::east((meridian_offset * 86400.0).round() as i32)
+}
+
+/// Gets the local instant of the Julian Date, shifted by the offset in days.
+/// Local midnights fall on the same JD as UTC midnights, so it can be compared with local dates.
+pub fn local_instant(jd: f64, meridian_offset: f64) -> f64 {
+    jd + meridian_offset
+}
+
+/// Gets the local date of the instant (as UTC midnight JD).
+/// Days in Julian Date start at noon, so the midnight is found by flooring from half a day before.
+pub fn local_day(jd: f64, meridian_offset: f64) -> f64 {
+    (local_instant(jd, meridian_offset) - 0.5).floor() + 0.5
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tempo::TempoCalendar;
+
+    /// Finds the month starting on the local date (as UTC midnight JD).
+    fn month_from<C: CalendarSystem>(calendar: &C, day: f64) -> (usize, bool) {
+        let month = calendar
+            .calculate_months(day)
+            .unwrap()
+            .into_iter()
+            .find(|m| m.jd_start == day)
+            .unwrap();
+        (month.month, month.leap_month)
+    }
+
+    #[test]
+    fn day_boundary_decides_chuki_containment() {
+        // Taisho at 2017-07-23 00:15 JST falls on 07-22 at the mean midnight of 120°E,
+        // which moves it into the month from 06-24 and the leap month after it
+        let (day_0624, day_0723) = (2457928.5, 2457957.5);
+        let shifted = TempoCalendar {
+            day_boundary: DayBoundary::MeanMidnight(120.0),
+            ..TEMPO
+        };
+
+        assert_eq!(month_from(&TEMPO, day_0624), (5, true));
+        assert_eq!(month_from(&TEMPO, day_0723), (6, false));
+        assert_eq!(month_from(&shifted, day_0624), (6, false));
+        assert_eq!(month_from(&shifted, day_0723), (6, true));
+    }
+}
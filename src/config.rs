@@ -0,0 +1,105 @@
+//! Contains the server configuration, read from the environment and arguments at the startup.
+//! The configuration is the state of the server, so handlers read it from requests.
+
+use std::{env, path::PathBuf, time::Duration};
+
+use anyhow::{bail, Result};
+
+/// Default maximum length of ranges in days, which is 400 Gregorian years.
+const DEFAULT_MAX_RANGE_DAYS: i64 = 146097;
+
+/// Default compute budget of a request in milliseconds.
+/// The longest ranges take about a second, so this stops only calculations which would not end.
+const DEFAULT_COMPUTE_BUDGET_MS: u64 = 10000;
+
+/// Represents the server configuration.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Config {
+    /// JPL ephemeris file to load, from `QREK_JPL_EPHEMERIS`.
+    pub jpl_ephemeris: Option<PathBuf>,
+
+    /// File to write the event table into instead of serving, from `QREK_WRITE_EVENT_TABLE`.
+    pub event_table_output: Option<PathBuf>,
+
+    /// Default solver tolerance in seconds, from `QREK_SOLVER_TOLERANCE`.
+    pub solver_tolerance: Option<f64>,
+
+    /// First and last years of the precomputed event table, from `QREK_PRECOMPUTED_YEARS`.
+    pub precomputed_years: Option<(i32, i32)>,
+
+    /// Maximum length of ranges in days, from `QREK_MAX_RANGE_DAYS`.
+    pub max_range_days: i64,
+
+    /// Compute budget of a request, from `QREK_COMPUTE_BUDGET_MS`.
+    pub compute_budget: Duration,
+
+    /// Reference data to validate against instead of serving, from `--validate <path>`.
+    pub validation_reference: Option<PathBuf>,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Config {
+            jpl_ephemeris: None,
+            event_table_output: None,
+            solver_tolerance: None,
+            precomputed_years: None,
+            max_range_days: DEFAULT_MAX_RANGE_DAYS,
+            compute_budget: Duration::from_millis(DEFAULT_COMPUTE_BUDGET_MS),
+            validation_reference: None,
+        }
+    }
+}
+
+impl Config {
+    /// Reads the configuration from the environment variables and the arguments.
+    pub fn from_env() -> Result<Config> {
+        let solver_tolerance = match env::var("QREK_SOLVER_TOLERANCE") {
+            Ok(seconds) => Some(seconds.parse()?),
+            Err(_) => None,
+        };
+        let precomputed_years = match env::var("QREK_PRECOMPUTED_YEARS") {
+            Ok(years) => match years.split_once('-') {
+                Some((first, last)) => Some((first.parse()?, last.parse()?)),
+                None => bail!("QREK_PRECOMPUTED_YEARS should be like 1844-2100"),
+            },
+            Err(_) => None,
+        };
+        let max_range_days = match env::var("QREK_MAX_RANGE_DAYS") {
+            Ok(days) => match days.parse() {
+                Ok(days) if days > 0 => days,
+                _ => bail!("QREK_MAX_RANGE_DAYS should be a positive number of days"),
+            },
+            Err(_) => DEFAULT_MAX_RANGE_DAYS,
+        };
+        let compute_budget_ms = match env::var("QREK_COMPUTE_BUDGET_MS") {
+            Ok(ms) => match ms.parse() {
+                Ok(ms) if ms > 0 => ms,
+                _ => bail!("QREK_COMPUTE_BUDGET_MS should be a positive number of milliseconds"),
+            },
+            Err(_) => DEFAULT_COMPUTE_BUDGET_MS,
+        };
+
+        let mut args = env::args_os().skip(1);
+        let mut validation_reference = None;
+        while let Some(arg) = args.next() {
+            match arg.to_str() {
+                Some("--validate") => match args.next() {
+                    Some(path) => validation_reference = Some(PathBuf::from(path)),
+                    None => bail!("--validate should be followed by the reference CSV"),
+                },
+                _ => bail!("Unknown argument {}", arg.to_string_lossy()),
+            }
+        }
+
+        Ok(Config {
+            jpl_ephemeris: env::var_os("QREK_JPL_EPHEMERIS").map(PathBuf::from),
+            event_table_output: env::var_os("QREK_WRITE_EVENT_TABLE").map(PathBuf::from),
+            solver_tolerance,
+            precomputed_years,
+            max_range_days,
+            compute_budget: Duration::from_millis(compute_budget_ms),
+            validation_reference,
+        })
+    }
+}
@@ -0,0 +1,149 @@
+//! Contains interpretation of input dates around the Gregorian reform.
+
+use anyhow::{bail, Result};
+use chrono::prelude::*;
+use serde::Deserialize;
+
+use crate::{
+    calendar::{meridian_timezone, JST_OFFSET},
+    error::ConversionError,
+};
+
+/// First day of the Gregorian calendar.
+const GREGORIAN_REFORM: (i32, u32, u32) = (1582, 10, 15);
+
+/// Formats accepted as input dates.
+pub const DATE_FORMATS: &[&str] = &[
+    "YYYY-MM-DD",
+    "YYYYMMDD",
+    "YYYY-MM-DDThh:mm:ss+09:00 (RFC 3339)",
+    "Unix time in seconds (except 8 digits)",
+];
+
+/// Julian Day Number of 0000-12-31 in the proleptic Gregorian calendar.
+const JDN_BEFORE_CE: i64 = 1721425;
+
+/// Represents a calendar which input dates are written in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DateSystem {
+    /// Proleptic Gregorian calendar for all dates.
+    #[default]
+    Gregorian,
+
+    /// Proleptic Julian calendar for all dates.
+    Julian,
+
+    /// Julian calendar before the Gregorian reform (1582-10-15), and Gregorian after it.
+    Auto,
+}
+
+impl DateSystem {
+    /// Parses the date into the date in the proleptic Gregorian calendar.
+    /// `YYYY-MM-DD` and `YYYYMMDD` are written in this system, and instants are taken as dates in JST.
+    pub fn parse(self, date: &str) -> Result<NaiveDate> {
+        if let Some(date) = parse_instant(date)? {
+            return Ok(date);
+        }
+        let (year, month, day) = split_date(date)?;
+        let julian = match self {
+            DateSystem::Gregorian => false,
+            DateSystem::Julian => true,
+            DateSystem::Auto => (year, month, day) < GREGORIAN_REFORM,
+        };
+
+        if !julian {
+            match NaiveDate::from_ymd_opt(year, month, day) {
+                Some(date) => return Ok(date),
+                None => bail!(malformed(date)),
+            }
+        }
+        if day == 0 || day > julian_month_days(year, month) {
+            bail!(malformed(date));
+        }
+
+        // Dates skipped by the reform are rejected in automatic mode
+        if self == DateSystem::Auto && (year, month, day) >= (1582, 10, 5) {
+            bail!(ConversionError::NonexistentDate {
+                requested: date.to_string(),
+            });
+        }
+        let days = julian_day_number(year, month, day) - JDN_BEFORE_CE;
+        match NaiveDate::from_num_days_from_ce_opt(days as i32) {
+            Some(date) => Ok(date),
+            None => bail!(ConversionError::NonexistentDate {
+                requested: date.to_string(),
+            }),
+        }
+    }
+}
+
+/// Parses RFC 3339 datetime or Unix time in seconds into the date in JST, if written so.
+fn parse_instant(date: &str) -> Result<Option<NaiveDate>> {
+    let jst = meridian_timezone(JST_OFFSET);
+    if date.contains(':') {
+        return match DateTime::parse_from_rfc3339(date) {
+            Ok(datetime) => Ok(Some(datetime.with_timezone(&jst).date().naive_local())),
+            Err(_) => bail!(malformed(date)),
+        };
+    }
+
+    // 8 digits are always `YYYYMMDD`, though they could be seconds in 1970s
+    let digits = date.strip_prefix('-').unwrap_or(date);
+    if digits.is_empty() || digits.len() == 8 || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return Ok(None);
+    }
+    let datetime = date
+        .parse::<i64>()
+        .ok()
+        .and_then(|seconds| seconds.checked_add(jst.local_minus_utc() as i64))
+        .and_then(|seconds| NaiveDateTime::from_timestamp_opt(seconds, 0));
+    match datetime {
+        Some(datetime) => Ok(Some(datetime.date())),
+        None => bail!(malformed(date)),
+    }
+}
+
+/// Splits `YYYY-MM-DD` or `YYYYMMDD` into numbers.
+fn split_date(date: &str) -> Result<(i32, u32, u32)> {
+    let parts: Vec<_> = if date.len() == 8 && date.bytes().all(|b| b.is_ascii_digit()) {
+        vec![&date[..4], &date[4..6], &date[6..]]
+    } else {
+        date.splitn(3, '-').collect()
+    };
+    let numbers = match parts.as_slice() {
+        [year, month, day] => (year.parse(), month.parse(), day.parse()),
+        _ => bail!(malformed(date)),
+    };
+    match numbers {
+        (Ok(year), Ok(month), Ok(day)) => Ok((year, month, day)),
+        _ => bail!(malformed(date)),
+    }
+}
+
+/// Makes the error of the date not in the accepted formats.
+fn malformed(date: &str) -> ConversionError {
+    ConversionError::MalformedDate {
+        requested: date.to_string(),
+        accepted: DATE_FORMATS,
+    }
+}
+
+/// Gets the number of days of the month in the Julian calendar.
+fn julian_month_days(year: i32, month: u32) -> u32 {
+    match month {
+        1 | 3 | 5 | 7 | 8 | 10 | 12 => 31,
+        4 | 6 | 9 | 11 => 30,
+        2 if year.rem_euclid(4) == 0 => 29,
+        2 => 28,
+        _ => 0,
+    }
+}
+
+/// Calculates Julian Day Number of the date in the Julian calendar.
+fn julian_day_number(year: i32, month: u32, day: u32) -> i64 {
+    let a = (14 - month as i64) / 12;
+    let y = year as i64 + 4800 - a;
+    let m = month as i64 + 12 * a - 3;
+    day as i64 + (153 * m + 2) / 5 + 365 * y + y.div_euclid(4) - 32083
+}
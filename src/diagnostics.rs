@@ -0,0 +1,67 @@
+//! Contains diagnostics of conversions, which are attached to reports of wrong dates.
+
+use anyhow::Result;
+use chrono::prelude::*;
+
+use crate::{
+    astro::{
+        julian::julian_day_of,
+        longitude::jcg78::{ensure_supported_date, Jcg78},
+        solver::RootDiagnostics,
+    },
+    calendar::CalendarSystem,
+    sekki::Sekki,
+    tempo::{diagnose_leading_24sekki, diagnose_sakus_with, TempoMonth},
+};
+
+/// Represents intermediate results of a conversion with the convergence of the solvers.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConversionDiagnostics {
+    /// Months calculated around the date.
+    pub months: Vec<TempoMonth>,
+
+    /// Sakus around the months, with the elongation as the residual.
+    pub sakus: Vec<RootDiagnostics>,
+
+    /// True sekkis around the months, with the difference of the sun longitude as the residual.
+    pub sekkis: Vec<(RootDiagnostics, Sekki)>,
+
+    /// Chukis which the calendar system uses to name the months.
+    pub chukis: Vec<(f64, Sekki)>,
+
+    /// Offset of the reference meridian from UTC in days.
+    pub meridian_offset: f64,
+}
+
+/// Diagnoses the conversion of the JST date in the calendar system.
+pub fn diagnose_conversion<C: CalendarSystem + ?Sized>(
+    date: NaiveDate,
+    calendar: &C,
+) -> Result<ConversionDiagnostics> {
+    ensure_supported_date(date)?;
+    let jd = julian_day_of(date) - calendar.meridian_offset();
+    let months = calendar.calculate_months(jd)?;
+
+    // Sakus and sekkis of the months fall within a day of their local dates
+    let jd_from = months.first().map_or(jd, |m| m.jd_start) - 1.0;
+    let jd_to = months.last().map_or(jd, |m| m.jd_end) + 1.0;
+    let sakus = diagnose_sakus_with(jd_from, jd_to, &Jcg78)?;
+
+    // Any instant between two sekkis leads to the former
+    let mut sekkis = vec![];
+    let mut sekki = diagnose_leading_24sekki(jd_to)?;
+    while sekki.0.root >= jd_from {
+        let jd_previous = sekki.0.root - 1.0;
+        sekkis.push(sekki);
+        sekki = diagnose_leading_24sekki(jd_previous)?;
+    }
+    sekkis.reverse();
+
+    Ok(ConversionDiagnostics {
+        months,
+        sakus,
+        sekkis,
+        chukis: calendar.chukis(jd_from, jd_to)?,
+        meridian_offset: calendar.meridian_offset(),
+    })
+}
@@ -0,0 +1,107 @@
+//! Contains error types reported to clients.
+
+use std::{
+    error::Error,
+    fmt::{Display, Formatter, Result as FmtResult},
+};
+
+use chrono::prelude::*;
+
+/// Represents an error of conversions caused by the input, or by the solvers on it.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConversionError {
+    /// The date or year is out of the range which the longitude model supports.
+    OutOfSupportedRange {
+        requested: String,
+        min: NaiveDate,
+        max: NaiveDate,
+    },
+
+    /// The date does not exist in the calendar.
+    NonexistentDate { requested: String },
+
+    /// The date parameter is not written in any of the accepted formats.
+    MalformedDate {
+        requested: String,
+        accepted: &'static [&'static str],
+    },
+
+    /// The range starts after its end.
+    ReversedRange { from: NaiveDate, to: NaiveDate },
+
+    /// The range is longer than the limit in days.
+    RangeTooLong {
+        from: NaiveDate,
+        to: NaiveDate,
+        max_days: i64,
+    },
+
+    /// The date precedes the adoption of the selected calendar.
+    BeforeAdoption {
+        requested: NaiveDate,
+        adoption: NaiveDate,
+    },
+
+    /// The combination of options is not supported.
+    UnsupportedOptions { requested: String },
+
+    /// The solver could not find the event around the Julian Date in the iteration limits,
+    /// even with the fallbacks.
+    SolverDidNotConverge { jd: f64 },
+
+    /// The calculation took longer than the compute budget in milliseconds.
+    ComputeBudgetExceeded { budget_ms: u64 },
+}
+
+impl Display for ConversionError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> FmtResult {
+        match self {
+            ConversionError::OutOfSupportedRange {
+                requested,
+                min,
+                max,
+            } => write!(
+                f,
+                "{} is out of supported range ({} to {})",
+                requested, min, max
+            ),
+            ConversionError::NonexistentDate { requested } => {
+                write!(f, "{} does not exist in the calendar", requested)
+            }
+            ConversionError::MalformedDate { requested, .. } => {
+                write!(f, "{} is not a valid date", requested)
+            }
+            ConversionError::ReversedRange { from, to } => {
+                write!(f, "The range start {} is after the end {}", from, to)
+            }
+            ConversionError::RangeTooLong { from, to, max_days } => write!(
+                f,
+                "The range from {} to {} is longer than {} days",
+                from, to, max_days
+            ),
+            ConversionError::BeforeAdoption {
+                requested,
+                adoption,
+            } => write!(
+                f,
+                "{} precedes the adoption of the calendar on {}",
+                requested, adoption
+            ),
+            ConversionError::UnsupportedOptions { requested } => {
+                write!(f, "{} is not supported", requested)
+            }
+            ConversionError::SolverDidNotConverge { jd } => {
+                write!(f, "Event calculation did not converge around JD {}", jd)
+            }
+            ConversionError::ComputeBudgetExceeded { budget_ms } => {
+                write!(
+                    f,
+                    "Calculation exceeded the compute budget of {} ms",
+                    budget_ms
+                )
+            }
+        }
+    }
+}
+
+impl Error for ConversionError {}
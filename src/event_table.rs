@@ -0,0 +1,223 @@
+//! Contains the precomputed table of sakus and 24-sekkis.
+//! Within the range of the table, instants are looked up by binary search instead of the solvers.
+//! The table from 1900 to 2100 is embedded in the binary, and others can be precomputed at startup.
+
+use std::{convert::TryInto, fs, path::Path, sync::OnceLock};
+
+use anyhow::{bail, Result};
+use chrono::prelude::*;
+
+use crate::{
+    astro::{
+        julian::to_julian_date,
+        longitude::jcg78::{ensure_supported_year, Jcg78},
+    },
+    cache_stats::{CacheCounters, CacheStats},
+    sekki::Sekki,
+    tempo::{diagnose_leading_24sekki, diagnose_sakus_with},
+};
+
+/// First year of the embedded table.
+pub const EMBEDDED_FIRST_YEAR: i32 = 1900;
+
+/// Last year of the embedded table.
+pub const EMBEDDED_LAST_YEAR: i32 = 2100;
+
+/// Serialized embedded table, written by `write_embedded`.
+const EMBEDDED_BYTES: &[u8] = include_bytes!("../data/event_table.bin");
+
+/// The precomputed table.
+static TABLE: OnceLock<EventTable> = OnceLock::new();
+
+/// The embedded table, deserialized on the first lookup.
+static EMBEDDED: OnceLock<EventTable> = OnceLock::new();
+
+/// Lookups of sakus and 24-sekkis in the tables.
+static LOOKUP_COUNTERS: CacheCounters = CacheCounters::new();
+
+/// Represents instants of sakus and 24-sekkis in a range of Julian Date.
+#[derive(Debug, Clone, PartialEq)]
+struct EventTable {
+    /// Start of the range.
+    jd_from: f64,
+
+    /// End of the range, exclusive.
+    jd_to: f64,
+
+    /// Sakus in the range in ascending order.
+    sakus: Vec<f64>,
+
+    /// 24-sekkis from the one leading the start to the one after the end, in ascending order.
+    sekkis: Vec<(f64, Sekki)>,
+}
+
+impl EventTable {
+    /// Calculates the table from the start of the first year to the end of the last year in UTC.
+    fn calculate(first_year: i32, last_year: i32) -> Result<EventTable> {
+        ensure_supported_year(first_year)?;
+        ensure_supported_year(last_year)?;
+        if first_year > last_year {
+            bail!(
+                "The first year {} is after the last year {}",
+                first_year,
+                last_year
+            );
+        }
+        let jd_from = to_julian_date(&Utc.ymd(first_year, 1, 1).and_hms(0, 0, 0));
+        let jd_to = to_julian_date(&Utc.ymd(last_year + 1, 1, 1).and_hms(0, 0, 0));
+
+        let sakus = diagnose_sakus_with(jd_from, jd_to, &Jcg78)?
+            .into_iter()
+            .map(|r| r.root)
+            .collect();
+
+        let mut sekkis = vec![];
+        let (root, sekki) = diagnose_leading_24sekki(jd_from)?;
+        let mut last_sekki = (root.root, sekki);
+        sekkis.push(last_sekki);
+        while last_sekki.0 < jd_to {
+            // Sekkis are 14.7 to 15.8 days apart
+            let (root, sekki) = diagnose_leading_24sekki(last_sekki.0 + 16.0)?;
+            last_sekki = (root.root, sekki);
+            sekkis.push(last_sekki);
+        }
+
+        Ok(EventTable {
+            jd_from,
+            jd_to,
+            sakus,
+            sekkis,
+        })
+    }
+
+    /// Serializes the table into little-endian bytes.
+    /// Sekkis are stored as instants following the number of the first one, since they are consecutive.
+    fn to_bytes(&self) -> Vec<u8> {
+        let first_sekki = self.sekkis.first().map_or(0, |s| s.1.to_number());
+        let mut bytes = vec![];
+        bytes.extend_from_slice(&self.jd_from.to_le_bytes());
+        bytes.extend_from_slice(&self.jd_to.to_le_bytes());
+        bytes.extend_from_slice(&(self.sakus.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&(self.sekkis.len() as u32).to_le_bytes());
+        bytes.extend_from_slice(&(first_sekki as u32).to_le_bytes());
+        for saku in &self.sakus {
+            bytes.extend_from_slice(&saku.to_le_bytes());
+        }
+        for (jd, _) in &self.sekkis {
+            bytes.extend_from_slice(&jd.to_le_bytes());
+        }
+        bytes
+    }
+
+    /// Deserializes the table from bytes written by `to_bytes`.
+    fn from_bytes(bytes: &[u8]) -> Result<EventTable> {
+        let read_f64 = |offset: usize| -> Result<f64> {
+            match bytes.get(offset..offset + 8) {
+                Some(b) => Ok(f64::from_le_bytes(b.try_into()?)),
+                None => bail!("Event table is too short"),
+            }
+        };
+        let read_u32 = |offset: usize| -> Result<usize> {
+            match bytes.get(offset..offset + 4) {
+                Some(b) => Ok(u32::from_le_bytes(b.try_into()?) as usize),
+                None => bail!("Event table is too short"),
+            }
+        };
+
+        let (jd_from, jd_to) = (read_f64(0)?, read_f64(8)?);
+        let (saku_count, sekki_count, first_sekki) = (read_u32(16)?, read_u32(20)?, read_u32(24)?);
+        let sakus = (0..saku_count)
+            .map(|i| read_f64(28 + i * 8))
+            .collect::<Result<Vec<_>>>()?;
+        let sekkis_offset = 28 + saku_count * 8;
+        let sekkis = (0..sekki_count)
+            .map(|i| {
+                let jd = read_f64(sekkis_offset + i * 8)?;
+                Ok((jd, Sekki::from_number((first_sekki + i) % 24)?))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        if bytes.len() != sekkis_offset + sekki_count * 8 {
+            bail!("Event table has trailing bytes");
+        }
+
+        Ok(EventTable {
+            jd_from,
+            jd_to,
+            sakus,
+            sekkis,
+        })
+    }
+
+    /// Looks up the leading 24-sekki, if the table covers the instant.
+    fn leading_24sekki(&self, jd_now: f64) -> Option<(f64, Sekki)> {
+        if !(self.jd_from..self.jd_to).contains(&jd_now) {
+            return None;
+        }
+        let next = self.sekkis.partition_point(|s| s.0 <= jd_now);
+        next.checked_sub(1).map(|i| self.sekkis[i])
+    }
+
+    /// Looks up sakus whose instants are in the range, if the table covers the range.
+    fn sakus(&self, jd_from: f64, jd_to: f64) -> Option<Vec<f64>> {
+        if jd_from < self.jd_from || jd_to > self.jd_to {
+            return None;
+        }
+        let first = self.sakus.partition_point(|&s| s < jd_from);
+        let last = self.sakus.partition_point(|&s| s < jd_to);
+        Some(self.sakus[first..last].to_vec())
+    }
+}
+
+/// Precomputes the table for the years, which can be done only once.
+pub fn precompute(first_year: i32, last_year: i32) -> Result<()> {
+    let table = EventTable::calculate(first_year, last_year)?;
+    if TABLE.set(table).is_err() {
+        bail!("Event table is already precomputed");
+    }
+    Ok(())
+}
+
+/// Calculates the table for the embedded range and writes it to the file.
+/// The file is embedded at compile time, so it should be regenerated when the solvers change.
+pub fn write_embedded(path: impl AsRef<Path>) -> Result<()> {
+    let table = EventTable::calculate(EMBEDDED_FIRST_YEAR, EMBEDDED_LAST_YEAR)?;
+    fs::write(path, table.to_bytes())?;
+    Ok(())
+}
+
+/// Returns the embedded table.
+fn embedded() -> &'static EventTable {
+    EMBEDDED.get_or_init(|| {
+        EventTable::from_bytes(EMBEDDED_BYTES).expect("Embedded event table should be valid")
+    })
+}
+
+/// Enumerates the tables to look up, the precomputed one first.
+fn tables() -> impl Iterator<Item = &'static EventTable> {
+    TABLE.get().into_iter().chain(Some(embedded()))
+}
+
+/// Looks up the leading 24-sekki in the tables, if covered.
+pub fn leading_24sekki(jd_now: f64) -> Option<(f64, Sekki)> {
+    let sekki = tables().find_map(|t| t.leading_24sekki(jd_now));
+    LOOKUP_COUNTERS.record(sekki.is_some());
+    sekki
+}
+
+/// Looks up sakus in the range in the tables, if covered.
+pub fn sakus(jd_from: f64, jd_to: f64) -> Option<Vec<f64>> {
+    let sakus = tables().find_map(|t| t.sakus(jd_from, jd_to));
+    LOOKUP_COUNTERS.record(sakus.is_some());
+    sakus
+}
+
+/// Gets the counters of the lookups, with the number of events in the loaded tables.
+pub fn cache_stats() -> CacheStats {
+    let entries = TABLE
+        .get()
+        .into_iter()
+        .chain(EMBEDDED.get())
+        .map(|t| t.sakus.len() + t.sekkis.len())
+        .sum();
+    LOOKUP_COUNTERS.stats(entries)
+}
@@ -0,0 +1,89 @@
+//! Contains explanations of month naming and intercalation.
+
+use anyhow::Result;
+use chrono::prelude::*;
+
+use crate::{
+    astro::julian::to_julian_date,
+    calendar::{month_boundaries, Calendar, MonthBoundaries, JST_OFFSET},
+    sekki::Sekki,
+    tempo::TempoMonth,
+};
+
+/// Represents intermediate state of the month determination.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonthExplanation {
+    /// The determined month.
+    pub month: TempoMonth,
+
+    /// Instant of the saku which starts this month.
+    pub jd_saku_start: f64,
+
+    /// Instant of the saku which starts the next month.
+    pub jd_saku_end: f64,
+
+    /// Chukis whose local dates are in this month.
+    pub chukis: Vec<(f64, Sekki)>,
+
+    /// Offset of the reference meridian from UTC in days.
+    pub meridian_offset: f64,
+
+    /// Human-readable reason of the naming.
+    pub reason: String,
+}
+
+/// Explains how the month containing the JST date is determined.
+pub fn explain_month(date: NaiveDate, calendar: Calendar) -> Result<MonthExplanation> {
+    let MonthBoundaries {
+        month,
+        jd_saku_start,
+        jd_saku_end,
+        meridian_offset,
+    } = month_boundaries(date, calendar)?;
+
+    let jd = to_julian_date(&Utc.from_utc_date(&date).and_hms(0, 0, 0)) - JST_OFFSET;
+    let system = calendar.system_at(jd);
+    let chukis: Vec<_> = system
+        .chukis(jd_saku_start - 1.0, jd_saku_end + 1.0)?
+        .into_iter()
+        .filter(|(jd, _)| (month.jd_start..month.jd_end).contains(&system.local_day(*jd)))
+        .collect();
+
+    let naming = chukis
+        .iter()
+        .find(|(_, sekki)| sekki.chuki_month() == month.month);
+    let reason = match (month.leap_month, chukis.as_slice(), naming) {
+        (true, _, _) => format!(
+            "No chuki falls in this month, so it is the leap month of {}",
+            month.month
+        ),
+        (false, [], _) => format!(
+            "No chuki falls in this month, but the year has no leap month; it follows as {}",
+            month.month
+        ),
+        (false, [_], Some((_, sekki))) => format!(
+            "Chuki {} falls in this month, so it is named {}",
+            sekki.to_japanese(),
+            month.month
+        ),
+        (false, _, Some((_, sekki))) => format!(
+            "{} chukis fall in this month; it is named {} by the chuki {}",
+            chukis.len(),
+            month.month,
+            sekki.to_japanese()
+        ),
+        (false, _, None) => format!(
+            "Months follow in order from the 11th month, where another month has two chukis; it is named {}",
+            month.month
+        ),
+    };
+
+    Ok(MonthExplanation {
+        month,
+        jd_saku_start,
+        jd_saku_end,
+        chukis,
+        meridian_offset,
+        reason,
+    })
+}
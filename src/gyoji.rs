@@ -0,0 +1,134 @@
+//! Contains annual events (年中行事) based on tempo calendar.
+
+use anyhow::Result;
+use chrono::prelude::*;
+
+use crate::{
+    astro::julian::{from_julian_date, to_julian_date},
+    calendar::JST_OFFSET,
+    tempo::{calculate_nearest_bochi, TempoDate},
+};
+
+/// Represents gosekku (五節句).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Sekku {
+    Jinjitsu,
+    Joshi,
+    Tango,
+    Tanabata,
+    Choyo,
+}
+
+impl Sekku {
+    /// All sekkus in order.
+    pub const ALL: [Sekku; 5] = [
+        Sekku::Jinjitsu,
+        Sekku::Joshi,
+        Sekku::Tango,
+        Sekku::Tanabata,
+        Sekku::Choyo,
+    ];
+
+    /// Gets Japanese string.
+    pub fn to_japanese(self) -> &'static str {
+        match self {
+            Sekku::Jinjitsu => "人日",
+            Sekku::Joshi => "上巳",
+            Sekku::Tango => "端午",
+            Sekku::Tanabata => "七夕",
+            Sekku::Choyo => "重陽",
+        }
+    }
+
+    /// Gets Hepburn romanization.
+    pub fn to_romaji(self) -> &'static str {
+        match self {
+            Sekku::Jinjitsu => "Jinjitsu",
+            Sekku::Joshi => "Jōshi",
+            Sekku::Tango => "Tango",
+            Sekku::Tanabata => "Tanabata",
+            Sekku::Choyo => "Chōyō",
+        }
+    }
+
+    /// Gets English translation.
+    pub fn to_english(self) -> &'static str {
+        match self {
+            Sekku::Jinjitsu => "human day",
+            Sekku::Joshi => "doll festival",
+            Sekku::Tango => "boys' festival",
+            Sekku::Tanabata => "star festival",
+            Sekku::Choyo => "chrysanthemum festival",
+        }
+    }
+
+    /// Gets month and day of this sekku.
+    pub fn month_day(self) -> (u32, u32) {
+        match self {
+            Sekku::Jinjitsu => (1, 7),
+            Sekku::Joshi => (3, 3),
+            Sekku::Tango => (5, 5),
+            Sekku::Tanabata => (7, 7),
+            Sekku::Choyo => (9, 9),
+        }
+    }
+
+    /// Gets the modern date, which is the same month and day in Gregorian calendar.
+    pub fn modern_date(self, year: i32) -> NaiveDate {
+        let (month, day) = self.month_day();
+        NaiveDate::from_ymd(year, month, day)
+    }
+
+    /// Calculates the original date, which is the same month and day in tempo calendar.
+    pub fn kyureki_date(self, year: usize) -> Result<NaiveDate> {
+        let (month, day) = self.month_day();
+        let tempo_date = TempoDate {
+            year,
+            month: month as usize,
+            day: day as usize,
+            ..Default::default()
+        };
+        tempo_date.to_gregory_date()
+    }
+}
+
+/// Calculates the date of lunar new year (旧正月), which is 1/1 in tempo calendar.
+pub fn lunar_new_year(year: usize) -> Result<NaiveDate> {
+    let tempo_date = TempoDate {
+        year,
+        ..Default::default()
+    };
+    tempo_date.to_gregory_date()
+}
+
+/// Calculates the dates of kyubon (旧盆), which are 7/13 to 7/16 in tempo calendar.
+pub fn kyubon(year: usize) -> Result<Vec<NaiveDate>> {
+    (13..=16)
+        .map(|day| {
+            let tempo_date = TempoDate {
+                year,
+                month: 7,
+                day,
+                ..Default::default()
+            };
+            tempo_date.to_gregory_date()
+        })
+        .collect()
+}
+
+/// Calculates the date of chushu no meigetsu (中秋の名月), which is 8/15 in tempo calendar,
+/// and the instant of the nearest full moon.
+pub fn chushu(year: usize) -> Result<(NaiveDate, DateTime<Utc>)> {
+    let tempo_date = TempoDate {
+        year,
+        month: 8,
+        day: 15,
+        ..Default::default()
+    };
+    let date = tempo_date.to_gregory_date()?;
+
+    // Starts from the evening of the date in JST
+    let jd_evening = to_julian_date(&Utc.from_utc_date(&date).and_hms(12, 0, 0)) - JST_OFFSET;
+    let full_moon = from_julian_date(calculate_nearest_bochi(jd_evening)?)?;
+    Ok((date, full_moon))
+}
@@ -0,0 +1,182 @@
+//! Contains historical Japanese calendars used before tempo calendar.
+//!
+//! These calendars divide the year into 24-sekkis evenly (平気法),
+//! so the chukis are derived from the mean tropical year of each calendar.
+//! Its epoch winter solstice is anchored on the astronomical one of the epoch year,
+//! and sakus are calculated as true new moons in the meridian of Kyoto.
+//!
+//! These are approximations, since each calendar calculated new moons by its own tables,
+//! and responses in them are flagged as `approximate`.
+//! Tests check them with dates of historical events.
+
+use anyhow::Result;
+use chrono::prelude::*;
+
+use crate::{
+    astro::{events::sun_reaches, julian::julian_day_of},
+    calendar::{local_day, CalendarSystem},
+    sekki::Sekki,
+};
+
+/// Offset of the local mean time in Kyoto (135.77E) from UTC, in days.
+const KYOTO_OFFSET: f64 = 135.77 / 360.0;
+
+/// Represents a calendar which uses mean sekkis.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MeanTermCalendar {
+    /// Gregorian year whose winter solstice is the epoch.
+    pub epoch_year: i32,
+
+    /// Length of the tropical year at the epoch in days.
+    pub tropical_year: f64,
+
+    /// Secular variation (消長) of the tropical year in days per year.
+    pub secular_variation: f64,
+
+    /// Offset of the reference meridian from UTC in days.
+    pub meridian_offset: f64,
+
+    /// Whether shinsaku (進朔) is applied; the month starts on the next day
+    /// if the saku falls on or after 3/4 of the day.
+    pub shinsaku: bool,
+}
+
+/// Senmyo calendar (宣明暦), used from 862 to 1684.
+/// Only its years from 1650 are in the supported range of the longitude model.
+pub const SENMYO: MeanTermCalendar = MeanTermCalendar {
+    epoch_year: 821,
+    tropical_year: 3068055.0 / 8400.0,
+    secular_variation: 0.0,
+    meridian_offset: KYOTO_OFFSET,
+    shinsaku: true,
+};
+
+/// Jokyo calendar (貞享暦), used from 1685 to 1754.
+/// Tropical year decreases with the secular variation in Shoushi calendar (授時暦).
+pub const JOKYO: MeanTermCalendar = MeanTermCalendar {
+    epoch_year: 1683,
+    tropical_year: 365.2417,
+    secular_variation: -0.000001,
+    meridian_offset: KYOTO_OFFSET,
+    shinsaku: false,
+};
+
+/// Horyaku calendar (宝暦暦), used from 1755 to 1797.
+pub const HORYAKU: MeanTermCalendar = MeanTermCalendar {
+    epoch_year: 1753,
+    tropical_year: 365.2416,
+    secular_variation: -0.000001,
+    meridian_offset: KYOTO_OFFSET,
+    shinsaku: false,
+};
+
+/// Kansei calendar (寛政暦), used from 1798 to 1843.
+pub const KANSEI: MeanTermCalendar = MeanTermCalendar {
+    epoch_year: 1796,
+    tropical_year: 365.242347,
+    secular_variation: 0.0,
+    meridian_offset: KYOTO_OFFSET,
+    shinsaku: false,
+};
+
+impl MeanTermCalendar {
+    /// Calculates the mean winter solstice of the epoch year.
+    fn epoch_toji(&self) -> Result<f64> {
+        let jd_guess = julian_day_of(NaiveDate::from_ymd(self.epoch_year, 12, 21));
+        sun_reaches(Sekki::Toji.longitude(), jd_guess)
+    }
+
+    /// Calculates the instant of the mean chuki counted from the epoch winter solstice.
+    fn mean_chuki(&self, jd_toji: f64, index: i64) -> f64 {
+        let years = index as f64 / 12.0;
+        jd_toji + years * self.tropical_year + self.secular_variation * years * years / 2.0
+    }
+}
+
+impl CalendarSystem for MeanTermCalendar {
+    fn meridian_offset(&self) -> f64 {
+        self.meridian_offset
+    }
+
+    fn chukis(&self, jd_from: f64, jd_to: f64) -> Result<Vec<(f64, Sekki)>> {
+        let jd_toji = self.epoch_toji()?;
+        let term = self.tropical_year / 12.0;
+        let first_index = ((jd_from - jd_toji) / term).floor() as i64 - 1;
+        let last_index = ((jd_to - jd_toji) / term).ceil() as i64 + 1;
+
+        let chukis = (first_index..=last_index)
+            .map(|index| {
+                let sekki_index = (Sekki::Toji.to_number() as i64 + index * 2).rem_euclid(24);
+                let sekki = Sekki::from_number(sekki_index as usize).expect("Should be rounded");
+                (self.mean_chuki(jd_toji, index), sekki)
+            })
+            .filter(|(jd, _)| (jd_from..jd_to).contains(jd))
+            .collect();
+        Ok(chukis)
+    }
+
+    fn month_start_day(&self, jd_saku: f64) -> f64 {
+        let day = local_day(jd_saku, self.meridian_offset);
+        let day_fraction = jd_saku + self.meridian_offset - day;
+        if self.shinsaku && day_fraction >= 0.75 {
+            day + 1.0
+        } else {
+            day
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{calendar::Calendar, tempo::TempoDate};
+    use chrono::prelude::*;
+
+    /// Converts the Gregory date in the calendar.
+    fn converted(calendar: Calendar, (y, m, d): (i32, u32, u32)) -> String {
+        TempoDate::from_gregory_date_in(Utc.ymd(y, m, d), calendar)
+            .unwrap()
+            .to_string()
+    }
+
+    #[test]
+    fn historical_events_are_converted() {
+        // Lunar years are numbered by the Gregorian years of their 1st months
+        let events = [
+            // 明暦の大火 (明暦3年1月18日), 寛文近江・若狭地震 (寛文2年5月1日)
+            (Calendar::Senmyo, (1657, 3, 2), "1657/01/18"),
+            (Calendar::Senmyo, (1662, 6, 16), "1662/05/01"),
+            // 元禄赤穂事件 (元禄15年12月14日), 宝永地震 (宝永4年10月4日), 宝永大噴火 (宝永4年11月23日)
+            (Calendar::Jokyo, (1703, 1, 30), "1702/12/14"),
+            (Calendar::Jokyo, (1707, 10, 28), "1707/10/04"),
+            (Calendar::Jokyo, (1707, 12, 16), "1707/11/23"),
+            // 島原大変 (寛政4年4月1日), 浅間山天明噴火 (天明3年7月8日)
+            (Calendar::Horyaku, (1792, 5, 21), "1792/04/01"),
+            (Calendar::Horyaku, (1783, 8, 5), "1783/07/08"),
+            // 伊能忠敬の没日 (文政元年4月13日), 大塩平八郎の乱 (天保8年2月19日)
+            (Calendar::Kansei, (1818, 5, 17), "1818/04/13"),
+            (Calendar::Kansei, (1837, 3, 25), "1837/02/19"),
+        ];
+        for (calendar, date, expected) in events {
+            assert_eq!(converted(calendar, date), expected, "{:?}", date);
+        }
+    }
+
+    #[test]
+    fn adoptions_start_years() {
+        // 貞享2年, 宝暦5年 and 寛政10年 start with the calendars
+        for calendar in [Calendar::Jokyo, Calendar::Horyaku, Calendar::Kansei] {
+            let adoption = calendar.adoption_date().unwrap();
+            let date = (adoption.year(), adoption.month(), adoption.day());
+            assert_eq!(converted(calendar, date), format!("{}/01/01", date.0));
+        }
+    }
+
+    #[test]
+    fn historical_calendars_are_approximate() {
+        let date = NaiveDate::from_ymd(1800, 1, 1);
+        assert!(Calendar::Kansei.is_approximate_from(date));
+        assert!(Calendar::Auto.is_approximate_from(date));
+        assert!(!Calendar::Tempo.is_approximate_from(date));
+        assert!(!Calendar::Auto.is_approximate_from(NaiveDate::from_ymd(1844, 2, 18)));
+    }
+}
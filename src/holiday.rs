@@ -0,0 +1,186 @@
+//! Contains Japanese national holidays (国民の祝日) calculations.
+
+use std::iter::successors;
+
+use anyhow::Result;
+use chrono::prelude::*;
+
+use crate::{
+    astro::longitude::jcg78::ensure_supported_year,
+    calendar::{meridian_timezone, JST_OFFSET},
+    sekki::Sekki,
+};
+
+/// Represents a holiday.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Holiday {
+    pub date: NaiveDate,
+    pub name: &'static str,
+}
+
+/// Holidays only once held by special laws.
+const SPECIAL_HOLIDAYS: &[(i32, u32, u32, &str)] = &[
+    (1959, 4, 10, "皇太子明仁親王の結婚の儀"),
+    (1989, 2, 24, "昭和天皇の大喪の礼"),
+    (1990, 11, 12, "即位礼正殿の儀"),
+    (1993, 6, 9, "皇太子徳仁親王の結婚の儀"),
+    (2019, 5, 1, "天皇の即位の日"),
+    (2019, 10, 22, "即位礼正殿の儀"),
+];
+
+/// Gets the holiday name of the date.
+pub fn holiday_name(date: NaiveDate) -> Result<Option<&'static str>> {
+    let holidays = holidays_in_year(date.year())?;
+    Ok(holidays
+        .into_iter()
+        .find(|h| h.date == date)
+        .map(|h| h.name))
+}
+
+/// Calculates all holidays in the year, including substitute holidays and citizens' holidays.
+pub fn holidays_in_year(year: i32) -> Result<Vec<Holiday>> {
+    ensure_supported_year(year)?;
+    let mut holidays = statutory_holidays(year)?;
+    holidays.sort_by_key(|h| h.date);
+
+    // Kokumin no kyujitsu: a day sandwiched by holidays
+    if year >= 1986 {
+        let sandwiched: Vec<_> = holidays
+            .windows(2)
+            .filter(|w| w[1].date.signed_duration_since(w[0].date).num_days() == 2)
+            .map(|w| w[0].date.succ())
+            .filter(|d| d.weekday() != Weekday::Sun)
+            .collect();
+        holidays.extend(sandwiched.into_iter().map(|date| Holiday {
+            date,
+            name: "国民の休日",
+        }));
+    }
+
+    // Furikae kyujitsu: the next non-holiday of a holiday on Sunday
+    if year >= 1973 {
+        let substitutes: Vec<_> = holidays
+            .iter()
+            .filter(|h| h.date.weekday() == Weekday::Sun)
+            .filter(|h| h.date >= NaiveDate::from_ymd(1973, 4, 12))
+            .filter_map(|h| {
+                let mut following = successors(Some(h.date.succ()), |d| d.succ_opt());
+                if year >= 2007 {
+                    following.find(|d| holidays.iter().all(|h| h.date != *d))
+                } else {
+                    following
+                        .next()
+                        .filter(|d| holidays.iter().all(|h| h.date != *d))
+                }
+            })
+            .collect();
+        holidays.extend(substitutes.into_iter().map(|date| Holiday {
+            date,
+            name: "振替休日",
+        }));
+    }
+
+    holidays.sort_by_key(|h| h.date);
+    Ok(holidays)
+}
+
+/// Calculates holidays defined by the law directly.
+fn statutory_holidays(year: i32) -> Result<Vec<Holiday>> {
+    if year < 1948 {
+        return Ok(vec![]);
+    }
+
+    let mut holidays = vec![];
+    let mut push = |month: u32, day: u32, name: &'static str| {
+        holidays.push(Holiday {
+            date: NaiveDate::from_ymd(year, month, day),
+            name,
+        });
+    };
+
+    if year >= 1949 {
+        push(1, 1, "元日");
+        match year {
+            y if y >= 2000 => push(1, nth_monday(year, 1, 2), "成人の日"),
+            _ => push(1, 15, "成人の日"),
+        }
+    }
+    if year >= 1967 {
+        push(2, 11, "建国記念の日");
+    }
+    if year >= 2020 {
+        push(2, 23, "天皇誕生日");
+    }
+    if year >= 1949 {
+        let (month, day) = equinox_day(year, Sekki::Shunbun)?;
+        push(month, day, "春分の日");
+        match year {
+            y if y >= 2007 => push(4, 29, "昭和の日"),
+            y if y >= 1989 => push(4, 29, "みどりの日"),
+            _ => push(4, 29, "天皇誕生日"),
+        }
+        push(5, 3, "憲法記念日");
+        if year >= 2007 {
+            push(5, 4, "みどりの日");
+        }
+        push(5, 5, "こどもの日");
+    }
+    match year {
+        2020 => push(7, 23, "海の日"),
+        2021 => push(7, 22, "海の日"),
+        y if y >= 2003 => push(7, nth_monday(year, 7, 3), "海の日"),
+        y if y >= 1996 => push(7, 20, "海の日"),
+        _ => (),
+    }
+    match year {
+        2020 => push(8, 10, "山の日"),
+        2021 => push(8, 8, "山の日"),
+        y if y >= 2016 => push(8, 11, "山の日"),
+        _ => (),
+    }
+    match year {
+        y if y >= 2003 => push(9, nth_monday(year, 9, 3), "敬老の日"),
+        y if y >= 1966 => push(9, 15, "敬老の日"),
+        _ => (),
+    }
+    let (month, day) = equinox_day(year, Sekki::Shubun)?;
+    push(month, day, "秋分の日");
+    match year {
+        2020 => push(7, 24, "スポーツの日"),
+        2021 => push(7, 23, "スポーツの日"),
+        y if y >= 2022 => push(10, nth_monday(year, 10, 2), "スポーツの日"),
+        y if y >= 2000 => push(10, nth_monday(year, 10, 2), "体育の日"),
+        y if y >= 1966 => push(10, 10, "体育の日"),
+        _ => (),
+    }
+    push(11, 3, "文化の日");
+    push(11, 23, "勤労感謝の日");
+    if (1989..=2018).contains(&year) {
+        push(12, 23, "天皇誕生日");
+    }
+
+    holidays.extend(
+        SPECIAL_HOLIDAYS
+            .iter()
+            .filter(|(y, _, _, _)| *y == year)
+            .map(|&(y, m, d, name)| Holiday {
+                date: NaiveDate::from_ymd(y, m, d),
+                name,
+            }),
+    );
+    Ok(holidays)
+}
+
+/// Calculates the JST date of the equinox.
+fn equinox_day(year: i32, equinox: Sekki) -> Result<(u32, u32)> {
+    let jst = meridian_timezone(JST_OFFSET);
+    let date = equinox.instant_in_year(year)?.with_timezone(&jst).date();
+    Ok((date.month(), date.day()))
+}
+
+/// Gets the day of nth Monday in the month.
+fn nth_monday(year: i32, month: u32, n: u32) -> u32 {
+    let first = NaiveDate::from_ymd(year, month, 1).weekday();
+    let first_monday = (7 - first.num_days_from_monday()) % 7 + 1;
+    first_monday + (n - 1) * 7
+}
@@ -0,0 +1,93 @@
+//! Contains conversions between numbers and kanji numerals (漢数字).
+
+use anyhow::{bail, Result};
+use serde::Deserialize;
+
+/// Kanji digits from 0 to 9.
+const DIGITS: [char; 10] = ['〇', '一', '二', '三', '四', '五', '六', '七', '八', '九'];
+
+/// Units in 4-digit groups.
+const SMALL_UNITS: [(char, usize); 3] = [('千', 1000), ('百', 100), ('十', 10)];
+
+/// Units of 4-digit groups.
+const LARGE_UNITS: [(char, usize); 2] = [('億', 100_000_000), ('万', 10_000)];
+
+/// Represents a style of kanji numerals.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KanjiStyle {
+    /// Standard notation with units, like 二十一.
+    #[default]
+    Standard,
+
+    /// Contracted notation which uses 廿 for 20s and 卅 for 30s, like 廿一.
+    Contracted,
+}
+
+/// Converts a number into kanji numerals.
+pub fn to_kanji(number: usize, style: KanjiStyle) -> String {
+    if number == 0 {
+        return DIGITS[0].to_string();
+    }
+
+    let mut kanji = String::new();
+    let mut rest = number;
+    for &(unit, value) in LARGE_UNITS.iter() {
+        if rest >= value {
+            push_group(&mut kanji, rest / value, style);
+            kanji.push(unit);
+            rest %= value;
+        }
+    }
+    push_group(&mut kanji, rest, style);
+    kanji
+}
+
+/// Pushes kanji numerals of a number less than 10000.
+fn push_group(kanji: &mut String, group: usize, style: KanjiStyle) {
+    for &(unit, value) in SMALL_UNITS.iter() {
+        let digit = group / value % 10;
+        match (style, value, digit) {
+            (_, _, 0) => (),
+            (KanjiStyle::Contracted, 10, 2) => kanji.push('廿'),
+            (KanjiStyle::Contracted, 10, 3) => kanji.push('卅'),
+            (_, _, 1) => kanji.push(unit),
+            _ => {
+                kanji.push(DIGITS[digit]);
+                kanji.push(unit);
+            }
+        }
+    }
+    if !group.is_multiple_of(10) {
+        kanji.push(DIGITS[group % 10]);
+    }
+}
+
+/// Parses kanji numerals in both styles, including positional notation like 二〇二四.
+#[allow(dead_code)]
+pub fn from_kanji(kanji: &str) -> Result<usize> {
+    if kanji.is_empty() {
+        bail!("Empty kanji numeral");
+    }
+
+    let mut total = 0;
+    let mut group = 0;
+    let mut digits: Option<usize> = None;
+    for c in kanji.chars() {
+        if let Some(digit) = DIGITS.iter().position(|&d| d == c) {
+            digits = Some(digits.unwrap_or(0) * 10 + digit);
+        } else if let Some(&(_, value)) = SMALL_UNITS.iter().find(|(u, _)| *u == c) {
+            group += digits.take().unwrap_or(1) * value;
+        } else if let Some(&(_, value)) = LARGE_UNITS.iter().find(|(u, _)| *u == c) {
+            total += (group + digits.take().unwrap_or(0)) * value;
+            group = 0;
+        } else {
+            match (c, digits) {
+                ('廿', None) => group += 20,
+                ('卅', None) => group += 30,
+                _ => bail!("Invalid kanji numeral: {}", kanji),
+            }
+        }
+    }
+    Ok(total + group + digits.unwrap_or(0))
+}
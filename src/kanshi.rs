@@ -0,0 +1,111 @@
+//! Contains sexagenary cycle (干支) manipulations.
+
+use anyhow::{bail, Result};
+use chrono::prelude::*;
+
+/// Julian Day Number of 0000-12-31 (proleptic Gregorian), the day before CE day 1.
+const JDN_CE_EPOCH: i64 = 1721425;
+
+/// Represents junishi (十二支).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Junishi {
+    Ne,
+    Ushi,
+    Tora,
+    U,
+    Tatsu,
+    Mi,
+    Uma,
+    Hitsuji,
+    Saru,
+    Tori,
+    Inu,
+    I,
+}
+
+#[allow(dead_code)]
+impl Junishi {
+    /// Gets Japanese string.
+    pub fn to_japanese(self) -> &'static str {
+        match self {
+            Junishi::Ne => "子",
+            Junishi::Ushi => "丑",
+            Junishi::Tora => "寅",
+            Junishi::U => "卯",
+            Junishi::Tatsu => "辰",
+            Junishi::Mi => "巳",
+            Junishi::Uma => "午",
+            Junishi::Hitsuji => "未",
+            Junishi::Saru => "申",
+            Junishi::Tori => "酉",
+            Junishi::Inu => "戌",
+            Junishi::I => "亥",
+        }
+    }
+
+    /// Gets Hepburn romanization.
+    pub fn to_romaji(self) -> &'static str {
+        match self {
+            Junishi::Ne => "Ne",
+            Junishi::Ushi => "Ushi",
+            Junishi::Tora => "Tora",
+            Junishi::U => "U",
+            Junishi::Tatsu => "Tatsu",
+            Junishi::Mi => "Mi",
+            Junishi::Uma => "Uma",
+            Junishi::Hitsuji => "Hitsuji",
+            Junishi::Saru => "Saru",
+            Junishi::Tori => "Tori",
+            Junishi::Inu => "Inu",
+            Junishi::I => "I",
+        }
+    }
+
+    /// Gets English translation.
+    pub fn to_english(self) -> &'static str {
+        match self {
+            Junishi::Ne => "rat",
+            Junishi::Ushi => "ox",
+            Junishi::Tora => "tiger",
+            Junishi::U => "rabbit",
+            Junishi::Tatsu => "dragon",
+            Junishi::Mi => "snake",
+            Junishi::Uma => "horse",
+            Junishi::Hitsuji => "sheep",
+            Junishi::Saru => "monkey",
+            Junishi::Tori => "rooster",
+            Junishi::Inu => "dog",
+            Junishi::I => "boar",
+        }
+    }
+
+    /// Converts into numeral index.
+    pub fn to_number(self) -> usize {
+        self as usize
+    }
+
+    /// Converts from numeral index.
+    pub fn from_number(index: usize) -> Result<Junishi> {
+        match index {
+            0 => Ok(Junishi::Ne),
+            1 => Ok(Junishi::Ushi),
+            2 => Ok(Junishi::Tora),
+            3 => Ok(Junishi::U),
+            4 => Ok(Junishi::Tatsu),
+            5 => Ok(Junishi::Mi),
+            6 => Ok(Junishi::Uma),
+            7 => Ok(Junishi::Hitsuji),
+            8 => Ok(Junishi::Saru),
+            9 => Ok(Junishi::Tori),
+            10 => Ok(Junishi::Inu),
+            11 => Ok(Junishi::I),
+            _ => bail!("Out of junishi index"),
+        }
+    }
+
+    /// Gets the junishi of the day.
+    pub fn of_day(date: NaiveDate) -> Junishi {
+        let jdn = date.num_days_from_ce() as i64 + JDN_CE_EPOCH;
+        Junishi::from_number((jdn + 1).rem_euclid(12) as usize).expect("Should be rounded by 12")
+    }
+}
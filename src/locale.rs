@@ -0,0 +1,181 @@
+//! Contains localization of calendar terms.
+
+use serde::Deserialize;
+
+use crate::{
+    age::Yakudoshi,
+    astro::{
+        eclipse::{LunarEclipseType, SolarEclipseType},
+        moon::{MoonPhase, Node},
+        planet::{Planet, PlanetEventKind},
+        sun::OrbitApsis,
+    },
+    gyoji::Sekku,
+    kanshi::Junishi,
+    sekki::Sekki,
+    tempo::{MonthName, Rokuyo, Shio},
+    youbi::Youbi,
+    zassetsu::Zassetsu,
+};
+
+/// Represents a language of terms in responses.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Locale {
+    /// Japanese, the original notation.
+    #[default]
+    Ja,
+
+    /// English translation.
+    En,
+}
+
+/// Represents a calendar term which can be localized.
+pub trait Localize {
+    /// Gets the string in the locale.
+    fn localize(self, locale: Locale) -> &'static str;
+}
+
+impl Localize for Rokuyo {
+    fn localize(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::Ja => self.to_japanese(),
+            Locale::En => self.to_english(),
+        }
+    }
+}
+
+impl Localize for Shio {
+    fn localize(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::Ja => self.to_japanese(),
+            Locale::En => self.to_english(),
+        }
+    }
+}
+
+impl Localize for MonthName {
+    fn localize(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::Ja => self.to_japanese(),
+            Locale::En => self.to_english(),
+        }
+    }
+}
+
+impl Localize for Sekki {
+    fn localize(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::Ja => self.to_japanese(),
+            Locale::En => self.to_english(),
+        }
+    }
+}
+
+impl Localize for Sekku {
+    fn localize(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::Ja => self.to_japanese(),
+            Locale::En => self.to_english(),
+        }
+    }
+}
+
+impl Localize for Junishi {
+    fn localize(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::Ja => self.to_japanese(),
+            Locale::En => self.to_english(),
+        }
+    }
+}
+
+impl Localize for Zassetsu {
+    fn localize(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::Ja => self.to_japanese(),
+            Locale::En => self.to_english(),
+        }
+    }
+}
+
+impl Localize for Yakudoshi {
+    fn localize(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::Ja => self.to_japanese(),
+            Locale::En => self.to_english(),
+        }
+    }
+}
+
+impl Localize for Youbi {
+    fn localize(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::Ja => self.to_japanese(),
+            Locale::En => self.to_english(),
+        }
+    }
+}
+
+impl Localize for MoonPhase {
+    fn localize(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::Ja => self.to_japanese(),
+            Locale::En => self.to_english(),
+        }
+    }
+}
+
+impl Localize for SolarEclipseType {
+    fn localize(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::Ja => self.to_japanese(),
+            Locale::En => self.to_english(),
+        }
+    }
+}
+
+impl Localize for LunarEclipseType {
+    fn localize(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::Ja => self.to_japanese(),
+            Locale::En => self.to_english(),
+        }
+    }
+}
+
+impl Localize for Planet {
+    fn localize(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::Ja => self.to_japanese(),
+            Locale::En => self.to_english(),
+        }
+    }
+}
+
+impl Localize for PlanetEventKind {
+    fn localize(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::Ja => self.to_japanese(),
+            Locale::En => self.to_english(),
+        }
+    }
+}
+
+impl Localize for Node {
+    fn localize(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::Ja => self.to_japanese(),
+            Locale::En => self.to_english(),
+        }
+    }
+}
+
+impl Localize for OrbitApsis {
+    fn localize(self, locale: Locale) -> &'static str {
+        match locale {
+            Locale::Ja => self.to_japanese(),
+            Locale::En => self.to_english(),
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! Contains lunisolar calendars used in other East Asian countries.
+//!
+//! These calendars follow the modern Chinese rule; the month containing toji is always the 11th,
+//! and only a year from an 11th month to the next with 13 months has a leap month,
+//! which is the first month without chuki.
+
+use anyhow::Result;
+
+use crate::{
+    astro::{
+        angle::Degree, events::sun_reaches, longitude::jcg78::sun_longitude, moon::SYNODIC_MONTH,
+    },
+    calendar::CalendarSystem,
+    sekki::Sekki,
+    tempo::{calculate_sakus, calculate_true_chukis, TempoMonth},
+};
+
+/// Represents a lunisolar calendar with the modern Chinese rule.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EastAsianCalendar {
+    /// Offset of the reference meridian from UTC in days.
+    pub meridian_offset: f64,
+}
+
+/// Korean calendar (음력) in UTC+9.
+pub const KOREAN: EastAsianCalendar = EastAsianCalendar {
+    meridian_offset: 9.0 / 24.0,
+};
+
+/// Vietnamese calendar (âm lịch) in UTC+7.
+pub const VIETNAMESE: EastAsianCalendar = EastAsianCalendar {
+    meridian_offset: 7.0 / 24.0,
+};
+
+impl CalendarSystem for EastAsianCalendar {
+    fn meridian_offset(&self) -> f64 {
+        self.meridian_offset
+    }
+
+    fn chukis(&self, jd_from: f64, jd_to: f64) -> Result<Vec<(f64, Sekki)>> {
+        calculate_true_chukis(jd_from, jd_to)
+    }
+
+    fn name_months(&self, month_days: &[f64], chukis: &[(f64, Sekki)]) -> Vec<TempoMonth> {
+        // Chukis belong to months by the local date at the meridian, as sakus do
+        let chuki_days: Vec<_> = chukis.iter().map(|(jd, _)| self.local_day(*jd)).collect();
+
+        // Month days must start from an 11th month and end at the next 11th month
+        let is_leap_year = month_days.len() == 14;
+        let mut leap_found = false;
+        let mut month = 10;
+        let mut months = Vec::with_capacity(month_days.len() - 1);
+        for days in month_days.windows(2) {
+            let (jd_start, jd_end) = (days[0], days[1]);
+            let has_chuki = chuki_days
+                .iter()
+                .any(|day| (jd_start..jd_end).contains(day));
+
+            let leap_month = is_leap_year && !leap_found && !has_chuki && !months.is_empty();
+            if leap_month {
+                leap_found = true;
+            } else {
+                month = month % 12 + 1;
+            }
+            months.push(TempoMonth {
+                leap_month,
+                month,
+                jd_start,
+                jd_end,
+            });
+        }
+        months
+    }
+
+    fn calculate_months(&self, jd: f64) -> Result<Vec<TempoMonth>> {
+        // Three tojis which surround the date with two years from 11th months
+        let toji_longitude = Sekki::Toji.longitude();
+        let jd_search = jd - 31.0;
+        let delta_l = Degree(sun_longitude(jd_search)).ahead_of(toji_longitude);
+        let jd_toji = sun_reaches(toji_longitude, jd_search - delta_l.0 * 365.2 / 360.0)?;
+        let jd_toji_next = sun_reaches(toji_longitude, jd_toji + 365.2)?;
+        let jd_toji_last = sun_reaches(toji_longitude, jd_toji_next + 365.2)?;
+
+        let mut months = vec![];
+        for &(jd_from, jd_to) in [(jd_toji, jd_toji_next), (jd_toji_next, jd_toji_last)].iter() {
+            let (day_from, day_to) = (self.local_day(jd_from), self.local_day(jd_to));
+
+            // Sakus from the 11th month to the next 11th month
+            let candidates = calculate_sakus(jd_from - SYNODIC_MONTH - 1.0, jd_to + 2.0)?;
+            let first = candidates
+                .iter()
+                .rposition(|s| self.month_start_day(*s) <= day_from)
+                .expect("Should have saku before toji");
+            let sakus: Vec<_> = candidates[first..]
+                .iter()
+                .copied()
+                .take_while(|s| self.month_start_day(*s) <= day_to)
+                .collect();
+
+            let month_days: Vec<_> = sakus.iter().map(|s| self.month_start_day(*s)).collect();
+            let chukis = self.chukis(sakus[0] - 1.0, sakus[sakus.len() - 1] + 1.0)?;
+            months.extend(self.name_months(&month_days, &chukis));
+        }
+        Ok(months)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tempo::{TempoDate, TEMPO};
+    use chrono::prelude::*;
+
+    /// Finds the month containing the local date (as UTC midnight JD).
+    fn month_on(calendar: &EastAsianCalendar, day: f64) -> TempoMonth {
+        calendar
+            .calculate_months(day)
+            .unwrap()
+            .into_iter()
+            .find(|m| (m.jd_start..m.jd_end).contains(&day))
+            .unwrap()
+    }
+
+    #[test]
+    fn months_start_on_local_dates_at_meridian() {
+        // The saku at 2020-02-23 15:33 UTC falls on 02-24 in Korea and 02-23 in Vietnam
+        let (day_23, day_24) = (2458902.5, 2458903.5);
+
+        let korean = month_on(&KOREAN, day_24);
+        assert_eq!((korean.month, korean.leap_month), (2, false));
+        assert_eq!(korean.jd_start, day_24);
+        assert_eq!(month_on(&KOREAN, day_23).month, 1);
+
+        let vietnamese = month_on(&VIETNAMESE, day_23);
+        assert_eq!((vietnamese.month, vietnamese.leap_month), (2, false));
+        assert_eq!(vietnamese.jd_start, day_23);
+    }
+
+    #[test]
+    fn ordinals_count_from_new_year_of_calendar() {
+        // Tết 1968 is on 01-29 in Vietnam, a day before the new year in Japan
+        let date = TempoDate::from_gregory_date_with(Utc.ymd(1968, 1, 29), &VIETNAMESE).unwrap();
+        assert_eq!(date.to_string(), "1968/01/01");
+        assert_eq!(date.ordinal(&VIETNAMESE).unwrap(), (1, 384));
+        assert!(date.ordinal(&TEMPO).is_err());
+    }
+}
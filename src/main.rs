@@ -1,19 +1,138 @@
+mod age;
 mod astro;
+mod budget;
+mod cache_stats;
+mod calendar;
+mod config;
+mod date_system;
+mod diagnostics;
+mod error;
+mod event_table;
+mod explain;
+mod gyoji;
+mod historical;
+mod holiday;
+mod kanji;
+mod kanshi;
+mod locale;
+mod lunisolar;
+mod range;
+mod response;
+mod sekki;
 mod tempo;
+mod validate;
+mod verify;
+mod youbi;
+mod zassetsu;
 
-use anyhow::Result;
-use async_std::prelude::*;
-use chrono::prelude::*;
-use log::error;
-use serde::Deserialize;
-use serde_json::json;
-use tide::{Request, Response, Result as TideResult, StatusCode};
+use std::collections::BTreeMap;
 
-use tempo::TempoDate;
+use age::{NewYear, Sex};
+use anyhow::{bail, Result};
+use async_std::{prelude::*, task};
+use chrono::{naive::MAX_DATE, prelude::*};
+use log::info;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tide::{
+    http::headers::{CACHE_CONTROL, RETRY_AFTER},
+    utils::After,
+    Body, Endpoint, Request, Response, Result as TideResult, Server, StatusCode,
+};
+
+use astro::{
+    delta_t, eclipse,
+    julian::{self, from_julian_date, to_julian_date, JulianDate},
+    leap_second,
+    longitude::{
+        jcg78::{accuracy_warning, ensure_supported_year},
+        jpl, sampled, Models, Precision, SunModel,
+    },
+    moon::{self, Apsis, FullMoonSize, MoonPhase},
+    planet::{self, Planet},
+    riseset::{self, Observer, RefractionKind, Twilight},
+    solver,
+    sun::{self, OrbitApsis},
+};
+use calendar::{
+    meridian_timezone, Calendar, CalendarSystem, DayBoundary, DayBoundaryKind, HistoricalPolicy,
+    TermMethod, JST_MERIDIAN, JST_OFFSET,
+};
+use config::Config;
+use date_system::DateSystem;
+use diagnostics::diagnose_conversion;
+use error::ConversionError;
+use gyoji::Sekku;
+use kanji::KanjiStyle;
+use locale::{Locale, Localize};
+use range::TempoDateRange;
+use response::{
+    CacheStatsResponse, DateConversionResponse, DiagnosticsResponse, ErrorResponse,
+    RoundTripViolationResponse, SekkiResponse, TempoDateResponse, YoubiResponse,
+};
+use sekki::Sekki;
+use tempo::{Rokuyo, RokuyoScheme, TempoCalendar, TempoDate, TEMPO};
+use zassetsu::{Doyo, Zassetsu};
+
+/// Cache-Control of responses about past dates, which never change.
+const CACHE_CONTROL_PAST: &str = "public, max-age=31536000, immutable";
+
+/// Cache-Control of responses about today or future dates.
+const CACHE_CONTROL_RECENT: &str = "public, max-age=3600";
+
+/// Formats accepted as `datetime` parameters.
+const DATETIME_FORMATS: &[&str] = &["YYYY-MM-DDThh:mm:ss+09:00 (RFC 3339)"];
+
+/// Cache-Control of responses about the server state.
+const CACHE_CONTROL_NONE: &str = "no-store";
 
 #[async_std::main]
 async fn main() -> Result<()> {
     pretty_env_logger::init();
+    let config = Config::from_env()?;
+    if let Some(path) = &config.jpl_ephemeris {
+        jpl::load(path)?;
+        info!("JPL ephemeris loaded from {}", path.display());
+    }
+    if let Some(path) = &config.event_table_output {
+        event_table::write_embedded(path)?;
+        info!("Event table written to {}", path.display());
+        return Ok(());
+    }
+    if let Some(seconds) = config.solver_tolerance {
+        solver::configure_tolerance(seconds)?;
+        info!("Solver tolerance configured to {} seconds", seconds);
+    }
+    if let Some((first_year, last_year)) = config.precomputed_years {
+        event_table::precompute(first_year, last_year)?;
+        info!(
+            "Sakus and sekkis precomputed from {} to {}",
+            first_year, last_year
+        );
+    }
+    if let Some(path) = &config.validation_reference {
+        let report = validate::validate(&validate::load_reference(path)?)?;
+        for mismatch in &report.mismatches {
+            println!(
+                "{}:{}: {} {} is {} in the reference, but computed {}",
+                path.display(),
+                mismatch.line,
+                mismatch.date,
+                mismatch.field,
+                mismatch.expected,
+                mismatch.computed
+            );
+        }
+        println!(
+            "{} rows validated, {} mismatches",
+            report.checked,
+            report.mismatches.len()
+        );
+        if !report.mismatches.is_empty() {
+            bail!("Calculated calendar differs from the reference");
+        }
+        return Ok(());
+    }
 
     let ctrlc = async {
         async_ctrlc::CtrlC::new()
@@ -22,45 +141,1564 @@ async fn main() -> Result<()> {
         Ok(())
     };
 
-    let app = async {
-        let mut app = tide::new();
-        app.at("/tempo_date").get(get_tempo_date);
-        app.listen("0.0.0.0:8000").await
-    };
+    let app = server(config).listen("0.0.0.0:8000");
     app.race(ctrlc).await?;
     Ok(())
 }
 
+/// Builds the server with the routes, which has the configuration as the state.
+fn server(config: Config) -> Server<Config> {
+    let mut app = tide::with_state(config);
+    app.with(After(handle_error));
+    app.at("/tempo_date").get(blocking(get_tempo_date));
+    app.at("/doyo_ushi").get(blocking(get_doyo_ushi));
+    app.at("/kyubon").get(blocking(get_kyubon));
+    app.at("/lunar_new_year").get(blocking(get_lunar_new_year));
+    app.at("/chushu").get(blocking(get_chushu));
+    app.at("/holidays").get(blocking(get_holidays));
+    app.at("/gosekku").get(blocking(get_gosekku));
+    app.at("/explain_month").get(blocking(get_explain_month));
+    app.at("/yakudoshi").get(blocking(get_yakudoshi));
+    app.at("/kazoedoshi").get(blocking(get_kazoedoshi));
+    app.at("/lunar_birthday").get(blocking(get_lunar_birthday));
+    app.at("/tempo_diff").get(blocking(get_tempo_diff));
+    app.at("/leap_month").get(blocking(get_leap_month));
+    app.at("/month_boundaries")
+        .get(blocking(get_month_boundaries));
+    app.at("/rokuyo_search").get(blocking(get_rokuyo_search));
+    app.at("/tempo_dates").get(blocking(get_tempo_dates));
+    app.at("/sun").get(blocking(get_sun));
+    app.at("/moon").get(blocking(get_moon));
+    app.at("/moon_riseset").get(blocking(get_moon_riseset));
+    app.at("/sun_position").get(blocking(get_sun_position));
+    app.at("/sidereal_time").get(blocking(get_sidereal_time));
+    app.at("/time_scales").get(blocking(get_time_scales));
+    app.at("/equinoxes").get(blocking(get_equinoxes));
+    app.at("/earth_apsides").get(blocking(get_earth_apsides));
+    app.at("/eclipses").get(blocking(get_eclipses));
+    app.at("/planet_events").get(blocking(get_planet_events));
+    app.at("/admin/cache/stats").get(blocking(get_cache_stats));
+    app.at("/admin/verify/round_trip")
+        .get(blocking(get_verify_round_trip));
+    app
+}
+
 /// GET `/tempo_date`
-async fn get_tempo_date(request: Request<()>) -> TideResult {
+fn get_tempo_date(request: Request<Config>) -> TideResult {
     #[derive(Debug, Clone, Deserialize)]
     struct QueryParameters {
         date: String,
+        #[serde(default)]
+        date_system: DateSystem,
+        #[serde(default)]
+        calendar: Calendar,
+        #[serde(default)]
+        historical: HistoricalPolicy,
+        #[serde(default)]
+        rokuyo: RokuyoScheme,
+        #[serde(default)]
+        locale: Locale,
+        #[serde(default)]
+        numeral: KanjiStyle,
+        #[serde(default)]
+        day_boundary: DayBoundaryKind,
+        boundary_longitude: Option<f64>,
+        #[serde(default)]
+        term_method: TermMethod,
+        #[serde(default)]
+        debug: bool,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        date_str: DateTime<FixedOffset>,
+        tempo_date_str: String,
+        tempo_date_kanji: String,
+        tempo_date: TempoDateResponse,
+        proleptic: bool,
+        approximate: bool,
+        holiday: Option<&'static str>,
+        sekki: Option<SekkiResponse>,
+        youbi: YoubiResponse,
+        moon_age: f64,
+        moon_illumination: f64,
+        moon_phase_str: &'static str,
+        moon_phase_romaji: &'static str,
+        shio_str: &'static str,
+        shio_romaji: &'static str,
+        ordinal: Option<usize>,
+        days_in_year: Option<usize>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        diagnostics: Option<DiagnosticsResponse>,
     }
 
     let query: QueryParameters = request.query()?;
-    let src_str = format!("{}T00:00:00+09:00", query.date);
-    let datetime = match DateTime::parse_from_str(&src_str, "%+") {
-        Ok(dt) => dt,
-        Err(e) => {
-            error!("DateTime parse error: {}", e);
-            return Err(e.into());
+    let datetime = parse_date_in(&query.date, query.date_system)?;
+    let date = datetime.date();
+    let day_boundary = query
+        .day_boundary
+        .at(query.boundary_longitude.unwrap_or(JST_MERIDIAN));
+    let (calendar, proleptic) = query
+        .calendar
+        .resolve(date.naive_local(), query.historical)?;
+    let tempo_date =
+        TempoDate::from_gregory_date_bounded(date, calendar, day_boundary, query.term_method)?;
+    let moon_age = tempo_date.moon_age()?;
+    let moon_phase = MoonPhase::from_lunar_age(moon_age);
+    let shio = tempo_date.shio()?;
+    let youbi = tempo_date.youbi()?;
+    let sekki = Sekki::on_jst_date(date.naive_local())?.map(|(sekki, instant)| SekkiResponse {
+        name: sekki.localize(query.locale),
+        name_romaji: sekki.to_romaji(),
+        instant: instant
+            .with_timezone(date.offset())
+            .to_rfc3339_opts(SecondsFormat::Millis, false),
+    });
+    // The same system as the conversion, which rejects options other than the standard
+    // for calendars other than tempo calendar
+    let bounded = TempoCalendar {
+        day_boundary,
+        term_method: query.term_method,
+        ..TEMPO
+    };
+    let system: &dyn CalendarSystem = match (day_boundary, query.term_method) {
+        (DayBoundary::Standard, TermMethod::True) => calendar.system_at(to_julian_date(&datetime)),
+        _ => &bounded,
+    };
+    // The next lunar new year can be out of the supported range
+    let ordinal = match tempo_date.ordinal(system) {
+        Ok(ordinal) => Some(ordinal),
+        Err(e) => match e.downcast_ref::<ConversionError>() {
+            Some(ConversionError::OutOfSupportedRange { .. }) => None,
+            _ => return Err(e.into()),
+        },
+    };
+
+    let diagnostics = if query.debug {
+        Some(diagnose_conversion(date.naive_local(), system)?)
+    } else {
+        None
+    };
+
+    let body = ResponseBody {
+        date_str: datetime,
+        tempo_date_str: tempo_date.to_string(),
+        tempo_date_kanji: tempo_date.to_kanji_string(query.numeral),
+        tempo_date: TempoDateResponse::new(&tempo_date, query.rokuyo, query.locale)?,
+        proleptic,
+        approximate: calendar.is_approximate_from(date.naive_local()),
+        holiday: holiday::holiday_name(date.naive_local())?,
+        sekki,
+        youbi: YoubiResponse::new(youbi, query.locale),
+        moon_age,
+        moon_illumination: tempo_date.moon_illumination(),
+        moon_phase_str: moon_phase.localize(query.locale),
+        moon_phase_romaji: moon_phase.to_romaji(),
+        shio_str: shio.localize(query.locale),
+        shio_romaji: shio.to_romaji(),
+        ordinal: ordinal.map(|(o, _)| o),
+        days_in_year: ordinal.map(|(_, d)| d),
+        diagnostics: diagnostics
+            .map(|d| DiagnosticsResponse::new(&d, query.locale))
+            .transpose()?,
+    };
+    json_response(&body, date.naive_local())
+}
+
+/// Parses `date` parameter as JST midnight.
+fn parse_date(date: &str) -> TideResult<DateTime<FixedOffset>> {
+    parse_date_in(date, DateSystem::Gregorian)
+}
+
+/// Parses `datetime` parameter in RFC 3339.
+fn parse_datetime(datetime: &str) -> TideResult<DateTime<FixedOffset>> {
+    match DateTime::parse_from_rfc3339(datetime) {
+        Ok(datetime) => Ok(datetime),
+        Err(_) => Err(ConversionError::MalformedDate {
+            requested: datetime.to_string(),
+            accepted: DATETIME_FORMATS,
         }
+        .into()),
+    }
+}
+
+/// Parses `date` parameter written in the date system as JST midnight.
+fn parse_date_in(date: &str, date_system: DateSystem) -> TideResult<DateTime<FixedOffset>> {
+    let date = date_system.parse(date)?;
+    Ok(meridian_timezone(JST_OFFSET)
+        .from_local_date(&date)
+        .single()
+        .expect("Should be unique in fixed offset")
+        .and_hms(0, 0, 0))
+}
+
+/// Converts errors into JSON responses.
+async fn handle_error(mut response: Response) -> TideResult {
+    if let Some(e) = response.downcast_error::<ConversionError>() {
+        // Malformed parameters are rejected before any conversion
+        let (status, accepted_formats) = match e {
+            ConversionError::MalformedDate { accepted, .. } => {
+                (StatusCode::BadRequest, Some(*accepted))
+            }
+            ConversionError::ReversedRange { .. } | ConversionError::RangeTooLong { .. } => {
+                (StatusCode::BadRequest, None)
+            }
+            ConversionError::SolverDidNotConverge { .. } => (StatusCode::InternalServerError, None),
+            // The calculation itself is valid, so it may succeed when the server is less loaded
+            ConversionError::ComputeBudgetExceeded { .. } => (StatusCode::ServiceUnavailable, None),
+            _ => (StatusCode::UnprocessableEntity, None),
+        };
+        let retry_after = match e {
+            ConversionError::ComputeBudgetExceeded { budget_ms } => Some(budget_ms.div_ceil(1000)),
+            _ => None,
+        };
+        let jd = match e {
+            ConversionError::SolverDidNotConverge { jd } => Some(*jd),
+            _ => None,
+        };
+        let body = ErrorResponse {
+            error: e.to_string(),
+            accepted_formats,
+            jd,
+        };
+        response.set_status(status);
+        response.set_body(Body::from_json(&body)?);
+        if let Some(seconds) = retry_after {
+            response.insert_header(RETRY_AFTER, seconds.to_string());
+        }
+    }
+    Ok(response)
+}
+
+/// Checks that the range of `from` and `to` parameters is within the configured length.
+fn ensure_range_length(config: &Config, from: NaiveDate, to: NaiveDate) -> Result<()> {
+    let max_days = config.max_range_days;
+    if (to - from).num_days() >= max_days {
+        return Err(ConversionError::RangeTooLong { from, to, max_days }.into());
+    }
+    Ok(())
+}
+
+/// Wraps the handler to run on the blocking thread pool.
+/// Calculations are CPU-bound, so running them on the executor would stall other requests.
+/// Each request is calculated within the configured compute budget.
+fn blocking(handler: fn(Request<Config>) -> TideResult) -> impl Endpoint<Config> {
+    move |request: Request<Config>| async move {
+        let budget = request.state().compute_budget;
+        task::spawn_blocking(move || budget::with_budget(budget, || handler(request))).await
+    }
+}
+
+/// Builds a successful response with the JSON body about dates up to the last date.
+fn json_response(body: &impl Serialize, last_date: NaiveDate) -> TideResult {
+    json_response_over(body, last_date, last_date)
+}
+
+/// Builds a successful response with the JSON body about dates in the range.
+/// Responses only about dates before today in JST never change, so they can be cached long.
+/// Bodies about dates out of the accurate range of the longitude model have `accuracy_warning`.
+fn json_response_over(
+    body: &impl Serialize,
+    first_date: NaiveDate,
+    last_date: NaiveDate,
+) -> TideResult {
+    let today = Utc::now()
+        .with_timezone(&meridian_timezone(JST_OFFSET))
+        .date()
+        .naive_local();
+    let cache_control = if last_date < today {
+        CACHE_CONTROL_PAST
+    } else {
+        CACHE_CONTROL_RECENT
     };
-    let date = datetime.date();
-    let tempo_date = TempoDate::from_gregory_date(date)?;
-
-    let body = json!({
-        "date_str": datetime,
-        "tempo_date_str": tempo_date.to_string(),
-        "tempo_date": {
-            "year": tempo_date.year,
-            "month": tempo_date.month,
-            "day": tempo_date.day,
-            "leap_month": tempo_date.leap_month,
-            "rokuyo_index": tempo_date.rokuyo().to_number(),
-            "rokuyo_str": tempo_date.rokuyo().to_japanese(),
+    let body = match accuracy_warning(first_date, last_date) {
+        Some(warning) => {
+            let mut value = serde_json::to_value(body)?;
+            if let Value::Object(fields) = &mut value {
+                fields.insert("accuracy_warning".to_string(), Value::String(warning));
+            }
+            Body::from_json(&value)?
         }
-    });
-    Ok(Response::builder(StatusCode::Ok).body(body).build())
+        None => Body::from_json(body)?,
+    };
+    Ok(Response::builder(StatusCode::Ok)
+        .header(CACHE_CONTROL, cache_control)
+        .body(body)
+        .build())
+}
+
+/// Gets the last date of the year, or the latest date if out of range.
+fn year_end(year: i32) -> NaiveDate {
+    NaiveDate::from_ymd_opt(year, 12, 31).unwrap_or(MAX_DATE)
+}
+
+/// GET `/doyo_ushi`
+fn get_doyo_ushi(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        year: i32,
+        #[serde(default)]
+        locale: Locale,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        year: i32,
+        name: &'static str,
+        name_romaji: &'static str,
+        doyo_start: NaiveDate,
+        doyo_end: NaiveDate,
+        ushi_days: Vec<NaiveDate>,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let doyo = Doyo::summer(query.year)?;
+
+    let body = ResponseBody {
+        year: query.year,
+        name: Zassetsu::Doyo.localize(query.locale),
+        name_romaji: Zassetsu::Doyo.to_romaji(),
+        doyo_start: doyo.start,
+        doyo_end: doyo.end,
+        ushi_days: doyo.ushi_days(),
+    };
+    json_response(&body, year_end(query.year))
+}
+
+/// GET `/kyubon`
+fn get_kyubon(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        year: usize,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        year: usize,
+        dates: Vec<NaiveDate>,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let dates = gyoji::kyubon(query.year)?;
+
+    let body = ResponseBody {
+        year: query.year,
+        dates,
+    };
+    json_response(&body, year_end(query.year as i32))
+}
+
+/// GET `/lunar_new_year`
+fn get_lunar_new_year(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        year: usize,
+        #[serde(default)]
+        locale: Locale,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        year: usize,
+        date: NaiveDate,
+        tempo_date_str: String,
+        tempo_date: TempoDateResponse,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let date = gyoji::lunar_new_year(query.year)?;
+    let tempo_date = TempoDate::from_gregory_date(Utc.from_utc_date(&date))?;
+
+    let body = ResponseBody {
+        year: query.year,
+        date,
+        tempo_date_str: tempo_date.to_string(),
+        tempo_date: TempoDateResponse::new(&tempo_date, RokuyoScheme::default(), query.locale)?,
+    };
+    json_response(&body, date)
+}
+
+/// GET `/chushu`
+fn get_chushu(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        year: usize,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        year: usize,
+        date: NaiveDate,
+        full_moon: DateTime<FixedOffset>,
+        full_moon_date: NaiveDate,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let (date, full_moon) = gyoji::chushu(query.year)?;
+    let jst = meridian_timezone(JST_OFFSET);
+
+    let body = ResponseBody {
+        year: query.year,
+        date,
+        full_moon: full_moon.round_subsecs(0).with_timezone(&jst),
+        full_moon_date: full_moon.with_timezone(&jst).date().naive_local(),
+    };
+    json_response(&body, date)
+}
+
+/// GET `/holidays`
+fn get_holidays(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        year: i32,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct HolidayItem {
+        date: NaiveDate,
+        name: &'static str,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        year: i32,
+        holidays: Vec<HolidayItem>,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let holidays = holiday::holidays_in_year(query.year)?
+        .into_iter()
+        .map(|h| HolidayItem {
+            date: h.date,
+            name: h.name,
+        })
+        .collect();
+
+    let body = ResponseBody {
+        year: query.year,
+        holidays,
+    };
+    json_response(&body, year_end(query.year))
+}
+
+/// GET `/gosekku`
+fn get_gosekku(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        year: i32,
+        #[serde(default)]
+        locale: Locale,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct SekkuItem {
+        name: &'static str,
+        name_romaji: &'static str,
+        date: NaiveDate,
+        kyureki_date: NaiveDate,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        year: i32,
+        gosekku: Vec<SekkuItem>,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let mut sekkus = vec![];
+    for sekku in Sekku::ALL.iter() {
+        let kyureki_date = sekku.kyureki_date(query.year as usize)?;
+        sekkus.push(SekkuItem {
+            name: sekku.localize(query.locale),
+            name_romaji: sekku.to_romaji(),
+            date: sekku.modern_date(query.year),
+            kyureki_date,
+        });
+    }
+
+    let body = ResponseBody {
+        year: query.year,
+        gosekku: sekkus,
+    };
+    json_response(&body, year_end(query.year))
+}
+
+/// GET `/explain_month`
+fn get_explain_month(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        date: String,
+        #[serde(default)]
+        date_system: DateSystem,
+        #[serde(default)]
+        calendar: Calendar,
+        #[serde(default)]
+        historical: HistoricalPolicy,
+        #[serde(default)]
+        locale: Locale,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ChukiItem {
+        name: &'static str,
+        name_romaji: &'static str,
+        instant: DateTime<FixedOffset>,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        month: usize,
+        leap_month: bool,
+        first_date: NaiveDate,
+        last_date: NaiveDate,
+        days: usize,
+        saku_start: DateTime<FixedOffset>,
+        saku_end: DateTime<FixedOffset>,
+        chukis: Vec<ChukiItem>,
+        reason: String,
+        proleptic: bool,
+        approximate: bool,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let datetime = parse_date_in(&query.date, query.date_system)?;
+    let date = datetime.date().naive_local();
+    let (calendar, proleptic) = query.calendar.resolve(date, query.historical)?;
+    let explanation = explain::explain_month(date, calendar)?;
+
+    let offset = meridian_timezone(explanation.meridian_offset);
+    let local_instant = |jd: f64| -> Result<_> {
+        Ok(from_julian_date(jd)?
+            .round_subsecs(0)
+            .with_timezone(&offset))
+    };
+    let local_date = |jd: f64| -> Result<_> { Ok(from_julian_date(jd)?.date().naive_utc()) };
+    let month = explanation.month;
+    let chukis = explanation
+        .chukis
+        .iter()
+        .map(|(jd, sekki)| {
+            Ok(ChukiItem {
+                name: sekki.localize(query.locale),
+                name_romaji: sekki.to_romaji(),
+                instant: local_instant(*jd)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let body = ResponseBody {
+        month: month.month,
+        leap_month: month.leap_month,
+        first_date: local_date(month.jd_start)?,
+        last_date: local_date(month.jd_end - 1.0)?,
+        days: month.days(),
+        saku_start: local_instant(explanation.jd_saku_start)?,
+        saku_end: local_instant(explanation.jd_saku_end)?,
+        chukis,
+        reason: explanation.reason,
+        proleptic,
+        approximate: calendar.is_approximate_from(date),
+    };
+    json_response(&body, body.last_date)
+}
+
+/// GET `/yakudoshi`
+fn get_yakudoshi(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        birth_date: String,
+        year: i32,
+        sex: Sex,
+        #[serde(default)]
+        locale: Locale,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        year: i32,
+        kazoedoshi: Option<usize>,
+        yakudoshi: Option<&'static str>,
+        yakudoshi_romaji: Option<&'static str>,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let birth_date = parse_date(&query.birth_date)?.date().naive_local();
+    let kazoedoshi = age::kazoedoshi_in_year(birth_date, query.year)?;
+    let yakudoshi = age::yakudoshi(birth_date, query.year, query.sex)?;
+
+    let body = ResponseBody {
+        year: query.year,
+        kazoedoshi,
+        yakudoshi: yakudoshi.map(|y| y.localize(query.locale)),
+        yakudoshi_romaji: yakudoshi.map(|y| y.to_romaji()),
+    };
+    json_response(&body, year_end(query.year))
+}
+
+/// GET `/kazoedoshi`
+fn get_kazoedoshi(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        birth_date: String,
+        date: String,
+        #[serde(default)]
+        new_year: NewYear,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        birth_date: NaiveDate,
+        date: NaiveDate,
+        kazoedoshi: usize,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let birth_date = parse_date(&query.birth_date)?.date().naive_local();
+    let date = parse_date(&query.date)?.date().naive_local();
+    let kazoedoshi = age::kazoedoshi(birth_date, date, query.new_year)?;
+
+    let body = ResponseBody {
+        birth_date,
+        date,
+        kazoedoshi,
+    };
+    json_response(&body, date)
+}
+
+/// GET `/lunar_birthday`
+fn get_lunar_birthday(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        birth_date: String,
+        year: usize,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        birth_date: NaiveDate,
+        birth_tempo_date_str: String,
+        year: usize,
+        date: NaiveDate,
+        tempo_date_str: String,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let birth_date = parse_date(&query.birth_date)?.date();
+    let birth_tempo_date = TempoDate::from_gregory_date(birth_date)?;
+    let date = age::lunar_birthday(birth_date.naive_local(), query.year)?;
+    let tempo_date = TempoDate::from_gregory_date(Utc.from_utc_date(&date))?;
+
+    let body = ResponseBody {
+        birth_date: birth_date.naive_local(),
+        birth_tempo_date_str: birth_tempo_date.to_string(),
+        year: query.year,
+        date,
+        tempo_date_str: tempo_date.to_string(),
+    };
+    json_response(&body, date)
+}
+
+/// GET `/tempo_diff`
+fn get_tempo_diff(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        from: String,
+        to: String,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        from_tempo_date_str: String,
+        to_tempo_date_str: String,
+        days: i64,
+        months: i64,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let (from_date, to_date) = (
+        parse_date(&query.from)?.date(),
+        parse_date(&query.to)?.date(),
+    );
+    let from = TempoDate::from_gregory_date(from_date)?;
+    let to = TempoDate::from_gregory_date(to_date)?;
+
+    let body = ResponseBody {
+        from_tempo_date_str: from.to_string(),
+        to_tempo_date_str: to.to_string(),
+        days: from.days_until(&to),
+        months: from.months_until(&to),
+    };
+    let (from_date, to_date) = (from_date.naive_local(), to_date.naive_local());
+    json_response_over(&body, from_date.min(to_date), from_date.max(to_date))
+}
+
+/// GET `/leap_month`
+fn get_leap_month(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        year: usize,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        year: usize,
+        is_leap_year: bool,
+        leap_month: Option<usize>,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let leap_month = tempo::leap_month_of_year(query.year)?;
+
+    let body = ResponseBody {
+        year: query.year,
+        is_leap_year: tempo::is_leap_lunar_year(query.year)?,
+        leap_month,
+    };
+    json_response(&body, year_end(query.year as i32 + 1))
+}
+
+/// GET `/month_boundaries`
+fn get_month_boundaries(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        date: String,
+        #[serde(default)]
+        date_system: DateSystem,
+        #[serde(default)]
+        calendar: Calendar,
+        #[serde(default)]
+        historical: HistoricalPolicy,
+        #[serde(flatten)]
+        models: Models,
+        tolerance: Option<f64>,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        month: usize,
+        leap_month: bool,
+        saku_start: DateTime<FixedOffset>,
+        saku_end: DateTime<FixedOffset>,
+        proleptic: bool,
+        approximate: bool,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let datetime = parse_date_in(&query.date, query.date_system)?;
+    let date = datetime.date().naive_local();
+    let (calendar, proleptic) = query.calendar.resolve(date, query.historical)?;
+    let boundaries = calendar::month_boundaries(date, calendar)?;
+    let models = Models {
+        tolerance: query.tolerance,
+        ..query.models
+    };
+    models.ensure_covers(boundaries.jd_saku_start - 3.0, boundaries.jd_saku_end + 3.0)?;
+
+    // Instants are refined in the models, which differ from the default by minutes
+    let refine = |jd: f64| tempo::calculate_leading_saku_with(jd + 0.5, &models);
+    let (jd_saku_start, jd_saku_end) = (
+        refine(boundaries.jd_saku_start)?,
+        refine(boundaries.jd_saku_end)?,
+    );
+
+    let offset = meridian_timezone(boundaries.meridian_offset);
+    let local_instant = |jd: f64| -> Result<_> {
+        Ok(from_julian_date(jd)?
+            .round_subsecs(0)
+            .with_timezone(&offset))
+    };
+    let month = boundaries.month;
+
+    let body = ResponseBody {
+        month: month.month,
+        leap_month: month.leap_month,
+        saku_start: local_instant(jd_saku_start)?,
+        saku_end: local_instant(jd_saku_end)?,
+        proleptic,
+        approximate: calendar.is_approximate_from(date),
+    };
+    json_response(&body, body.saku_end.date().naive_local())
+}
+
+/// GET `/rokuyo_search`
+fn get_rokuyo_search(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        from: String,
+        to: String,
+        rokuyo: Rokuyo,
+        #[serde(default)]
+        rokuyo_scheme: RokuyoScheme,
+        #[serde(default)]
+        calendar: Calendar,
+        #[serde(default)]
+        historical: HistoricalPolicy,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        rokuyo: &'static str,
+        dates: Vec<DateConversionResponse>,
+        proleptic: bool,
+        approximate: bool,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let from = parse_date(&query.from)?.date().naive_local();
+    let to = parse_date(&query.to)?.date().naive_local();
+    let (calendar, proleptic) = query.calendar.resolve(from, query.historical)?;
+    let range = TempoDateRange::new(from, to, calendar)?;
+    ensure_range_length(request.state(), from, to)?;
+    let dates = range
+        .filter_rokuyo(query.rokuyo, query.rokuyo_scheme)
+        .map(|d| DateConversionResponse::new(&d?))
+        .collect::<Result<Vec<_>>>()?;
+
+    let body = ResponseBody {
+        rokuyo: query.rokuyo.to_japanese(),
+        dates,
+        proleptic,
+        approximate: calendar.is_approximate_from(from),
+    };
+    json_response_over(&body, from, to)
+}
+
+/// GET `/tempo_dates`
+fn get_tempo_dates(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        from: String,
+        to: String,
+        #[serde(default)]
+        calendar: Calendar,
+        #[serde(default)]
+        historical: HistoricalPolicy,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        dates: Vec<DateConversionResponse>,
+        proleptic: bool,
+        approximate: bool,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let from = parse_date(&query.from)?.date().naive_local();
+    let to = parse_date(&query.to)?.date().naive_local();
+
+    let (calendar, proleptic) = query.calendar.resolve(from, query.historical)?;
+    let range = TempoDateRange::new(from, to, calendar)?;
+    ensure_range_length(request.state(), from, to)?;
+    let dates = range
+        .map(|d| DateConversionResponse::new(&d?))
+        .collect::<Result<Vec<_>>>()?;
+
+    let body = ResponseBody {
+        dates,
+        proleptic,
+        approximate: calendar.is_approximate_from(from),
+    };
+    json_response_over(&body, from, to)
+}
+
+/// GET `/sun`
+fn get_sun(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        date: String,
+        lat: f64,
+        lon: f64,
+        depression: Option<f64>,
+        #[serde(default)]
+        refraction: RefractionKind,
+        pressure: Option<f64>,
+        temperature: Option<f64>,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct TwilightItem {
+        depression: f64,
+        dawn: Option<DateTime<FixedOffset>>,
+        dusk: Option<DateTime<FixedOffset>>,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        date: NaiveDate,
+        sunrise: Option<DateTime<FixedOffset>>,
+        transit: Option<DateTime<FixedOffset>>,
+        sunset: Option<DateTime<FixedOffset>>,
+        equation_of_time: f64,
+        twilight: BTreeMap<&'static str, TwilightItem>,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let date = parse_date(&query.date)?.date().naive_local();
+    let observer = Observer {
+        latitude: query.lat,
+        longitude: query.lon,
+    };
+
+    let jd_midnight = to_julian_date(&Utc.from_utc_date(&date).and_hms(0, 0, 0));
+    let jd_noon = sun::solar_noon(jd_midnight, query.lon);
+    let refraction = query.refraction.with(query.pressure, query.temperature);
+    let riseset = riseset::sun_riseset(jd_noon, &observer, refraction);
+    let jst = meridian_timezone(JST_OFFSET);
+    let jst_instant =
+        |jd: f64| -> Result<_> { Ok(from_julian_date(jd)?.round_subsecs(0).with_timezone(&jst)) };
+    let twilight_item = |depression: f64| -> Result<_> {
+        let twilight = riseset::twilight(jd_noon, &observer, depression);
+        Ok(TwilightItem {
+            depression,
+            dawn: twilight.rise.map(jst_instant).transpose()?,
+            dusk: twilight.set.map(jst_instant).transpose()?,
+        })
+    };
+
+    let mut twilights = BTreeMap::new();
+    for twilight in Twilight::ALL.iter() {
+        twilights.insert(twilight.name(), twilight_item(twilight.depression())?);
+    }
+    if let Some(depression) = query.depression {
+        twilights.insert("custom", twilight_item(depression)?);
+    }
+
+    let body = ResponseBody {
+        date,
+        sunrise: riseset.rise.map(jst_instant).transpose()?,
+        transit: riseset.transit.map(jst_instant).transpose()?,
+        sunset: riseset.set.map(jst_instant).transpose()?,
+        equation_of_time: sun::equation_of_time(jd_noon),
+        twilight: twilights,
+    };
+    json_response(&body, date)
+}
+
+/// GET `/moon`
+fn get_moon(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        date: String,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct FullMoonItem {
+        instant: DateTime<FixedOffset>,
+        distance: f64,
+        size: &'static str,
+        supermoon: bool,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ApsisItem {
+        name: &'static str,
+        instant: DateTime<FixedOffset>,
+        distance: f64,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        date: NaiveDate,
+        distance: f64,
+        latitude: f64,
+        illumination: f64,
+        nearest_full_moon: FullMoonItem,
+        next_perigee: ApsisItem,
+        next_apogee: ApsisItem,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let date = parse_date(&query.date)?.date().naive_local();
+    ensure_supported_year(date.year())?;
+
+    // Evaluated at JST noon
+    let jd_noon = to_julian_date(&Utc.from_utc_date(&date).and_hms(12, 0, 0)) - JST_OFFSET;
+    let jst = meridian_timezone(JST_OFFSET);
+    let jst_instant =
+        |jd: f64| -> Result<_> { Ok(from_julian_date(jd)?.round_subsecs(0).with_timezone(&jst)) };
+
+    let jd_full_moon = tempo::calculate_nearest_bochi(jd_noon)?;
+    let full_moon_distance = moon::distance(jd_full_moon);
+    let full_moon_size = FullMoonSize::from_distance(full_moon_distance);
+    let apsis_item = |apsis: Apsis| -> Result<_> {
+        let jd = moon::next_apsis(jd_noon, apsis)?;
+        Ok(ApsisItem {
+            name: apsis.to_english(),
+            instant: jst_instant(jd)?,
+            distance: moon::distance(jd),
+        })
+    };
+
+    let body = ResponseBody {
+        date,
+        distance: moon::distance(jd_noon),
+        latitude: moon::latitude(jd_noon),
+        illumination: moon::illumination(jd_noon),
+        nearest_full_moon: FullMoonItem {
+            instant: jst_instant(jd_full_moon)?,
+            distance: full_moon_distance,
+            size: full_moon_size.to_english(),
+            supermoon: full_moon_size == FullMoonSize::Supermoon,
+        },
+        next_perigee: apsis_item(Apsis::Perigee)?,
+        next_apogee: apsis_item(Apsis::Apogee)?,
+    };
+    json_response(&body, date)
+}
+
+/// GET `/moon_riseset`
+fn get_moon_riseset(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        date: String,
+        lat: f64,
+        lon: f64,
+        #[serde(default)]
+        refraction: RefractionKind,
+        pressure: Option<f64>,
+        temperature: Option<f64>,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        date: NaiveDate,
+        moonrise: Option<DateTime<FixedOffset>>,
+        transit: Option<DateTime<FixedOffset>>,
+        moonset: Option<DateTime<FixedOffset>>,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let date = parse_date(&query.date)?.date().naive_local();
+    let observer = Observer {
+        latitude: query.lat,
+        longitude: query.lon,
+    };
+
+    // The day is in the local mean time
+    let jd_midnight = to_julian_date(&Utc.from_utc_date(&date).and_hms(0, 0, 0));
+    let refraction = query.refraction.with(query.pressure, query.temperature);
+    let riseset = riseset::moon_riseset(jd_midnight - query.lon / 360.0, &observer, refraction);
+    let jst = meridian_timezone(JST_OFFSET);
+    let jst_instant =
+        |jd: f64| -> Result<_> { Ok(from_julian_date(jd)?.round_subsecs(0).with_timezone(&jst)) };
+
+    let body = ResponseBody {
+        date,
+        moonrise: riseset.rise.map(jst_instant).transpose()?,
+        transit: riseset.transit.map(jst_instant).transpose()?,
+        moonset: riseset.set.map(jst_instant).transpose()?,
+    };
+    json_response(&body, date)
+}
+
+/// GET `/sun_position`
+fn get_sun_position(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        datetime: String,
+        lat: f64,
+        lon: f64,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        datetime: DateTime<FixedOffset>,
+        right_ascension: f64,
+        declination: f64,
+        altitude: f64,
+        azimuth: f64,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let datetime = parse_datetime(&query.datetime)?;
+    let observer = Observer {
+        latitude: query.lat,
+        longitude: query.lon,
+    };
+    let position = sun::position(to_julian_date(&datetime), &observer);
+
+    let body = ResponseBody {
+        datetime,
+        right_ascension: position.right_ascension,
+        declination: position.declination,
+        altitude: position.altitude,
+        azimuth: position.azimuth,
+    };
+    json_response(&body, datetime.date().naive_local())
+}
+
+/// GET `/sidereal_time`
+fn get_sidereal_time(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        datetime: String,
+        #[serde(default)]
+        lon: f64,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        datetime: DateTime<FixedOffset>,
+        jd: f64,
+        mjd: f64,
+        greenwich_mean: f64,
+        greenwich_apparent: f64,
+        local_mean: f64,
+        local_apparent: f64,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let datetime = parse_datetime(&query.datetime)?;
+    let jd_utc = JulianDate::from_datetime(&datetime);
+    let jd = jd_utc.to_ut1().value();
+
+    // Reported in hours as well as almanacs do
+    let body = ResponseBody {
+        datetime,
+        jd: jd_utc.value(),
+        mjd: jd_utc.modified(),
+        greenwich_mean: julian::greenwich_mean_sidereal_time(jd) / 15.0,
+        greenwich_apparent: julian::greenwich_apparent_sidereal_time(jd) / 15.0,
+        local_mean: julian::local_mean_sidereal_time(jd, query.lon) / 15.0,
+        local_apparent: julian::local_apparent_sidereal_time(jd, query.lon) / 15.0,
+    };
+    json_response(&body, datetime.date().naive_local())
+}
+
+/// GET `/time_scales`
+fn get_time_scales(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        datetime: String,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        datetime: DateTime<FixedOffset>,
+        utc: f64,
+        ut1: f64,
+        tai: f64,
+        tt: f64,
+        tai_minus_utc: Option<f64>,
+        delta_t: f64,
+        tt_minus_utc: f64,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let datetime = parse_datetime(&query.datetime)?;
+    let jd_utc = JulianDate::from_datetime(&datetime);
+    let (jd_tai, jd_tt) = (jd_utc.to_tai(), jd_utc.to_tt());
+
+    // Differences are reported in seconds
+    let body = ResponseBody {
+        datetime,
+        utc: jd_utc.value(),
+        ut1: jd_utc.to_ut1().value(),
+        tai: jd_tai.value(),
+        tt: jd_tt.value(),
+        tai_minus_utc: leap_second::tai_minus_utc(jd_utc.modified()),
+        delta_t: delta_t::delta_t_at(jd_utc.value()),
+        tt_minus_utc: (jd_tt.value() - jd_utc.value()) * 86400.0,
+    };
+    json_response(&body, datetime.date().naive_local())
+}
+
+/// GET `/equinoxes`
+fn get_equinoxes(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        year: i32,
+        #[serde(default)]
+        locale: Locale,
+        /// Defaults to VSOP87, since instants of jcg78 can be off by minutes.
+        model: Option<SunModel>,
+        #[serde(default)]
+        precision: Precision,
+        tolerance: Option<f64>,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct EventItem {
+        name: &'static str,
+        name_romaji: &'static str,
+        jd: f64,
+        instant: String,
+        date: NaiveDate,
+        holiday: Option<&'static str>,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        year: i32,
+        events: Vec<EventItem>,
+    }
+
+    let query: QueryParameters = request.query()?;
+    ensure_supported_year(query.year)?;
+    let jst = meridian_timezone(JST_OFFSET);
+    let models = Models {
+        sun_model: query.model.unwrap_or(SunModel::Vsop87),
+        precision: query.precision,
+        tolerance: query.tolerance,
+        ..Default::default()
+    };
+    let jd_year = to_julian_date(&Utc.ymd(query.year, 1, 1).and_hms(0, 0, 0));
+    models.ensure_covers(jd_year, jd_year + 366.0)?;
+    let events = Sekki::EQUINOXES_AND_SOLSTICES
+        .iter()
+        .map(|sekki| {
+            let jd = sekki.jd_in_year(query.year, &models)?;
+            let instant = from_julian_date(jd)?.with_timezone(&jst);
+            Ok(EventItem {
+                name: sekki.localize(query.locale),
+                name_romaji: sekki.to_romaji(),
+                jd,
+                instant: instant.to_rfc3339_opts(SecondsFormat::Millis, false),
+                date: instant.date().naive_local(),
+                holiday: match sekki {
+                    Sekki::Shunbun => Some("春分の日"),
+                    Sekki::Shubun => Some("秋分の日"),
+                    _ => None,
+                },
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let body = ResponseBody {
+        year: query.year,
+        events,
+    };
+    json_response(&body, year_end(query.year))
+}
+
+/// GET `/earth_apsides`
+fn get_earth_apsides(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        year: i32,
+        #[serde(default)]
+        locale: Locale,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ApsisItem {
+        name: &'static str,
+        jd: f64,
+        instant: DateTime<FixedOffset>,
+        date: NaiveDate,
+        distance: f64,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        year: i32,
+        perihelion: ApsisItem,
+        aphelion: ApsisItem,
+    }
+
+    let query: QueryParameters = request.query()?;
+    ensure_supported_year(query.year)?;
+    let jst = meridian_timezone(JST_OFFSET);
+    let apsis_item = |apsis: OrbitApsis| -> Result<_> {
+        let jd = sun::apsis_in_year(query.year, apsis)?;
+        let instant = from_julian_date(jd)?.round_subsecs(0).with_timezone(&jst);
+        Ok(ApsisItem {
+            name: apsis.localize(query.locale),
+            jd,
+            instant,
+            date: instant.date().naive_local(),
+            distance: sun::distance(jd),
+        })
+    };
+
+    let body = ResponseBody {
+        year: query.year,
+        perihelion: apsis_item(OrbitApsis::Perihelion)?,
+        aphelion: apsis_item(OrbitApsis::Aphelion)?,
+    };
+    json_response(&body, year_end(query.year))
+}
+
+/// GET `/eclipses`
+fn get_eclipses(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        year: i32,
+        #[serde(default)]
+        locale: Locale,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct SolarEclipseItem {
+        instant: DateTime<FixedOffset>,
+        #[serde(rename = "type")]
+        kind: &'static str,
+        gamma: f64,
+        magnitude: f64,
+        node: &'static str,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ContactsItem {
+        begin: DateTime<FixedOffset>,
+        end: DateTime<FixedOffset>,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct LunarEclipseItem {
+        instant: DateTime<FixedOffset>,
+        #[serde(rename = "type")]
+        kind: &'static str,
+        gamma: f64,
+        penumbral_magnitude: f64,
+        umbral_magnitude: f64,
+        penumbral: ContactsItem,
+        partial: Option<ContactsItem>,
+        total: Option<ContactsItem>,
+        node: &'static str,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct SeasonItem {
+        node: &'static str,
+        start: DateTime<FixedOffset>,
+        middle: DateTime<FixedOffset>,
+        end: DateTime<FixedOffset>,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        year: i32,
+        solar: Vec<SolarEclipseItem>,
+        lunar: Vec<LunarEclipseItem>,
+        seasons: Vec<SeasonItem>,
+    }
+
+    let query: QueryParameters = request.query()?;
+    ensure_supported_year(query.year)?;
+    let jst = meridian_timezone(JST_OFFSET);
+    let jst_instant =
+        |jd: f64| -> Result<_> { Ok(from_julian_date(jd)?.round_subsecs(0).with_timezone(&jst)) };
+
+    // The year is in JST
+    let jd_year = |year: i32| to_julian_date(&Utc.ymd(year, 1, 1).and_hms(0, 0, 0)) - JST_OFFSET;
+    let (jd_from, jd_to) = (jd_year(query.year), jd_year(query.year + 1));
+    let solar = eclipse::solar_eclipses(jd_from, jd_to)
+        .into_iter()
+        .map(|e| {
+            Ok(SolarEclipseItem {
+                instant: jst_instant(e.jd)?,
+                kind: e.kind.localize(query.locale),
+                gamma: e.gamma,
+                magnitude: e.magnitude,
+                node: e.node.localize(query.locale),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let contacts_item = |contacts: (f64, f64)| -> Result<_> {
+        Ok(ContactsItem {
+            begin: jst_instant(contacts.0)?,
+            end: jst_instant(contacts.1)?,
+        })
+    };
+    let lunar = eclipse::lunar_eclipses(jd_from, jd_to)
+        .into_iter()
+        .map(|e| {
+            Ok(LunarEclipseItem {
+                instant: jst_instant(e.jd)?,
+                kind: e.kind.localize(query.locale),
+                gamma: e.gamma,
+                penumbral_magnitude: e.penumbral_magnitude,
+                umbral_magnitude: e.umbral_magnitude,
+                penumbral: contacts_item(e.penumbral)?,
+                partial: e.partial.map(contacts_item).transpose()?,
+                total: e.total.map(contacts_item).transpose()?,
+                node: e.node.localize(query.locale),
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+    let seasons = eclipse::eclipse_seasons(jd_from, jd_to)
+        .into_iter()
+        .map(|s| {
+            Ok(SeasonItem {
+                node: s.node.localize(query.locale),
+                start: jst_instant(s.jd_start)?,
+                middle: jst_instant(s.jd_middle)?,
+                end: jst_instant(s.jd_end)?,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let body = ResponseBody {
+        year: query.year,
+        solar,
+        lunar,
+        seasons,
+    };
+    json_response(&body, year_end(query.year))
+}
+
+/// GET `/planet_events`
+fn get_planet_events(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        year: i32,
+        #[serde(default)]
+        locale: Locale,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct EventItem {
+        planet: &'static str,
+        planet_romaji: &'static str,
+        kind: &'static str,
+        instant: DateTime<FixedOffset>,
+        latitude: f64,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        year: i32,
+        events: Vec<EventItem>,
+    }
+
+    let query: QueryParameters = request.query()?;
+    ensure_supported_year(query.year)?;
+    let jst = meridian_timezone(JST_OFFSET);
+    let jd_year = |year: i32| to_julian_date(&Utc.ymd(year, 1, 1).and_hms(0, 0, 0)) - JST_OFFSET;
+    let (jd_from, jd_to) = (jd_year(query.year), jd_year(query.year + 1));
+
+    let mut events: Vec<_> = Planet::ALL
+        .iter()
+        .flat_map(|&p| planet::events(p, jd_from, jd_to))
+        .collect();
+    events.sort_by(|a, b| a.jd.total_cmp(&b.jd));
+    let events = events
+        .into_iter()
+        .map(|e| {
+            Ok(EventItem {
+                planet: e.planet.localize(query.locale),
+                planet_romaji: e.planet.to_romaji(),
+                kind: e.kind.localize(query.locale),
+                instant: from_julian_date(e.jd)?.round_subsecs(0).with_timezone(&jst),
+                latitude: e.latitude,
+            })
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let body = ResponseBody {
+        year: query.year,
+        events,
+    };
+    json_response(&body, year_end(query.year))
+}
+
+/// GET `/admin/cache/stats`
+fn get_cache_stats(_request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        year_months: CacheStatsResponse,
+        event_table: CacheStatsResponse,
+        longitude_samples: CacheStatsResponse,
+    }
+
+    let body = ResponseBody {
+        year_months: CacheStatsResponse::new(&tempo::year_months_cache_stats()),
+        event_table: CacheStatsResponse::new(&event_table::cache_stats()),
+        longitude_samples: CacheStatsResponse::new(&sampled::cache_stats()),
+    };
+    Ok(Response::builder(StatusCode::Ok)
+        .header(CACHE_CONTROL, CACHE_CONTROL_NONE)
+        .body(Body::from_json(&body)?)
+        .build())
+}
+
+/// GET `/admin/verify/round_trip`
+fn get_verify_round_trip(request: Request<Config>) -> TideResult {
+    #[derive(Debug, Clone, Deserialize)]
+    struct QueryParameters {
+        from: String,
+        to: String,
+    }
+
+    #[derive(Debug, Clone, Serialize)]
+    struct ResponseBody {
+        from: NaiveDate,
+        to: NaiveDate,
+        checked: usize,
+        violations: Vec<RoundTripViolationResponse>,
+    }
+
+    let query: QueryParameters = request.query()?;
+    let from = parse_date(&query.from)?.date().naive_local();
+    let to = parse_date(&query.to)?.date().naive_local();
+    ensure_range_length(request.state(), from, to)?;
+
+    let report = verify::verify_round_trip(from, to)?;
+    let body = ResponseBody {
+        from,
+        to,
+        checked: report.checked,
+        violations: report
+            .violations
+            .iter()
+            .map(RoundTripViolationResponse::new)
+            .collect(),
+    };
+    json_response_over(&body, from, to)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tide::http::{self, Method, Url};
+
+    /// Requests the path to the server and gets the status.
+    fn status_of(app: &Server<Config>, path: &str) -> StatusCode {
+        let url = Url::parse("http://localhost/").unwrap().join(path).unwrap();
+        let response: http::Response =
+            task::block_on(app.respond(http::Request::new(Method::Get, url))).unwrap();
+        response.status()
+    }
+
+    #[test]
+    fn max_range_days_is_read_from_state() {
+        let app = server(Config {
+            max_range_days: 31,
+            ..Config::default()
+        });
+        assert_eq!(
+            status_of(&app, "/tempo_dates?from=2021-01-01&to=2021-01-31"),
+            StatusCode::Ok
+        );
+        assert_eq!(
+            status_of(&app, "/tempo_dates?from=2021-01-01&to=2021-02-01"),
+            StatusCode::BadRequest
+        );
+    }
+
+    #[test]
+    fn dates_out_of_supported_range_are_rejected() {
+        let app = server(Config::default());
+        for path in [
+            "/tempo_date?date=1600-06-01",
+            "/tempo_date?date=1649-12-31",
+            "/tempo_date?date=2200-01-01",
+            "/tempo_date?date=2300-06-01",
+        ] {
+            assert_eq!(
+                status_of(&app, path),
+                StatusCode::UnprocessableEntity,
+                "{}",
+                path
+            );
+        }
+        for path in [
+            "/tempo_date?date=1650-01-01",
+            "/tempo_date?date=1650-01-01&historical=dispatch",
+            "/tempo_date?date=2199-12-31",
+        ] {
+            assert_eq!(status_of(&app, path), StatusCode::Ok, "{}", path);
+        }
+    }
 }
@@ -0,0 +1,283 @@
+//! Contains iteration over ranges of tempo dates.
+
+use std::collections::VecDeque;
+#[cfg(feature = "parallel")]
+use std::iter;
+
+use anyhow::Result;
+use chrono::prelude::*;
+#[cfg(feature = "parallel")]
+use rayon::prelude::*;
+
+#[cfg(feature = "parallel")]
+use crate::budget;
+use crate::{
+    astro::{julian::julian_day_of, longitude::jcg78::ensure_supported_date},
+    calendar::{Calendar, JST_OFFSET},
+    error::ConversionError,
+    tempo::{Rokuyo, RokuyoScheme, TempoDate, TempoMonth},
+};
+
+/// Days of a part calculated on a thread, with `parallel` feature.
+#[cfg(feature = "parallel")]
+const PART_DAYS: f64 = 365.0;
+
+/// Iterates months which overlap the range.
+/// Month tables are reused while they contain the next month, so a table serves many months.
+#[derive(Debug, Clone)]
+pub struct TempoMonths {
+    cursor: TempoCalendarCursor,
+    jd_next: f64,
+    jd_last: f64,
+    /// Months calculated ahead, with `parallel` feature.
+    calculated: VecDeque<TempoMonth>,
+}
+
+impl TempoMonths {
+    /// Creates an iteration over the months overlapping the range.
+    fn new(calendar: Calendar, jd_first: f64, jd_last: f64) -> TempoMonths {
+        TempoMonths {
+            cursor: TempoCalendarCursor::new(calendar),
+            jd_next: jd_first,
+            jd_last,
+            calculated: VecDeque::new(),
+        }
+    }
+
+    /// Calculates months in the window of years ahead, one year for each thread of the pool.
+    /// Months are independent of each other, so years are calculated concurrently,
+    /// and the window bounds the months calculated ahead of the consumer.
+    #[cfg(feature = "parallel")]
+    fn calculate_in_parallel(&mut self) {
+        let days = (self.jd_last - self.jd_next).max(0.0).floor() + 1.0;
+        // Parts shorter than a year would mostly share the same month tables
+        let parts = rayon::current_num_threads().min((days / PART_DAYS).ceil() as usize);
+        let starts: Vec<_> = (0..parts)
+            .map(|i| self.jd_next + PART_DAYS * i as f64)
+            .collect();
+        let jd_window_last = (self.jd_next + PART_DAYS * parts as f64 - 1.0).min(self.jd_last);
+
+        // Parts share the compute budget of the calculation spawning them
+        let deadline = budget::deadline();
+        let calendar = self.cursor.calendar;
+        let results: Vec<Vec<Result<TempoMonth>>> = starts
+            .par_iter()
+            .enumerate()
+            .map(|(i, &jd_start)| {
+                let jd_end = starts.get(i + 1).map_or(jd_window_last, |s| s - 1.0);
+                let mut part = TempoMonths::new(calendar, jd_start, jd_end);
+                // The month containing the start is in the previous part unless it starts here
+                budget::with_deadline(deadline, || {
+                    iter::from_fn(|| part.next_sequential())
+                        .filter(|m| i == 0 || m.as_ref().map_or(true, |m| m.jd_start >= jd_start))
+                        .collect()
+                })
+            })
+            .collect();
+
+        // Months after a failure are calculated again sequentially to report the error
+        let jd_resume = self.jd_next;
+        for month in results.into_iter().flatten() {
+            match month {
+                Ok(month) => self.calculated.push_back(month),
+                Err(_) => break,
+            }
+        }
+        self.jd_next = self.calculated.back().map_or(jd_resume, |m| m.jd_end);
+    }
+
+    /// Gets the next month from the current month table, or calculates a new one.
+    fn next_sequential(&mut self) -> Option<Result<TempoMonth>> {
+        if self.jd_next > self.jd_last {
+            return None;
+        }
+
+        let month = match self.cursor.month_containing(self.jd_next) {
+            Ok(month) => month,
+            Err(e) => {
+                self.jd_next = f64::INFINITY;
+                return Some(Err(e));
+            }
+        };
+        self.jd_next = month.jd_end;
+        Some(Ok(month))
+    }
+}
+
+impl Iterator for TempoMonths {
+    type Item = Result<TempoMonth>;
+
+    fn next(&mut self) -> Option<Result<TempoMonth>> {
+        #[cfg(feature = "parallel")]
+        if self.calculated.is_empty() && self.jd_next <= self.jd_last {
+            self.calculate_in_parallel();
+        }
+        match self.calculated.pop_front() {
+            Some(month) => Some(Ok(month)),
+            None => self.next_sequential(),
+        }
+    }
+}
+
+/// Represents a range of JST dates, both ends inclusive.
+#[derive(Debug, Clone)]
+pub struct TempoDateRange {
+    jd_first: f64,
+    jd_last: f64,
+    months: TempoMonths,
+    pending: VecDeque<TempoDate>,
+}
+
+impl TempoDateRange {
+    /// Creates a range in the calendar.
+    pub fn new(first: NaiveDate, last: NaiveDate, calendar: Calendar) -> Result<TempoDateRange> {
+        ensure_supported_date(first)?;
+        ensure_supported_date(last)?;
+        if first > last {
+            return Err(ConversionError::ReversedRange {
+                from: first,
+                to: last,
+            }
+            .into());
+        }
+
+        let jd_first = julian_day_of(first);
+        let jd_last = julian_day_of(last);
+        Ok(TempoDateRange {
+            jd_first,
+            jd_last,
+            months: TempoMonths::new(calendar, jd_first, jd_last),
+            pending: VecDeque::new(),
+        })
+    }
+
+    /// Filters the dates by rokuyo, stepping 6 days in each month.
+    pub fn filter_rokuyo(self, rokuyo: Rokuyo, scheme: RokuyoScheme) -> FilterRokuyo {
+        FilterRokuyo {
+            range: self,
+            rokuyo,
+            scheme,
+        }
+    }
+
+    /// Gets the dates in the month which are also in the range, stepping by the days.
+    fn dates_in_month(
+        &self,
+        month: &TempoMonth,
+        first_day: usize,
+        step: usize,
+    ) -> Result<Vec<TempoDate>> {
+        let year = month.lunar_year()? as usize;
+        let jd_from = month.jd_start.max(self.jd_first);
+        let jd_to = (month.jd_end - 1.0).min(self.jd_last);
+
+        Ok((first_day..=month.days())
+            .step_by(step)
+            .map(|day| TempoDate {
+                year,
+                leap_month: month.leap_month,
+                month: month.month,
+                day,
+                jd: month.jd_start,
+            })
+            .filter(|d| (jd_from..=jd_to).contains(&d.jd_date()))
+            .collect())
+    }
+}
+
+impl Iterator for TempoDateRange {
+    type Item = Result<TempoDate>;
+
+    fn next(&mut self) -> Option<Result<TempoDate>> {
+        while self.pending.is_empty() {
+            let month = match self.months.next()? {
+                Ok(month) => month,
+                Err(e) => return Some(Err(e)),
+            };
+            match self.dates_in_month(&month, 1, 1) {
+                Ok(dates) => self.pending.extend(dates),
+                Err(e) => return Some(Err(e)),
+            }
+        }
+        self.pending.pop_front().map(Ok)
+    }
+}
+
+/// Iterates dates in the range which have the rokuyo.
+#[derive(Debug, Clone)]
+pub struct FilterRokuyo {
+    range: TempoDateRange,
+    rokuyo: Rokuyo,
+    scheme: RokuyoScheme,
+}
+
+impl Iterator for FilterRokuyo {
+    type Item = Result<TempoDate>;
+
+    fn next(&mut self) -> Option<Result<TempoDate>> {
+        while self.range.pending.is_empty() {
+            let month = match self.range.months.next()? {
+                Ok(month) => month,
+                Err(e) => return Some(Err(e)),
+            };
+            // Rokuyo cycles every 6 days in a month, so the first matching day decides the rest
+            let first_day =
+                (1..=6).find(|&day| self.scheme.rokuyo(month.month, day) == self.rokuyo);
+            if let Some(first_day) = first_day {
+                match self.range.dates_in_month(&month, first_day, 6) {
+                    Ok(dates) => self.range.pending.extend(dates),
+                    Err(e) => return Some(Err(e)),
+                }
+            }
+        }
+        self.range.pending.pop_front().map(Ok)
+    }
+}
+
+/// Finds months containing successive JST dates, reusing the months calculated for the previous dates.
+/// Months are calculated again only when a date goes out of them.
+#[derive(Debug, Clone)]
+struct TempoCalendarCursor {
+    calendar: Calendar,
+    /// The calendar resolved for the months and the months.
+    context: Option<(Calendar, Vec<TempoMonth>)>,
+}
+
+impl TempoCalendarCursor {
+    /// Creates a cursor in the calendar.
+    fn new(calendar: Calendar) -> TempoCalendarCursor {
+        TempoCalendarCursor {
+            calendar,
+            context: None,
+        }
+    }
+
+    /// Gets the month containing the JST date (as UTC midnight JD).
+    fn month_containing(&mut self, jd_date: f64) -> Result<TempoMonth> {
+        let jd = jd_date - JST_OFFSET;
+        let calendar = match self.calendar {
+            Calendar::Auto => Calendar::in_use_at(jd),
+            calendar => calendar,
+        };
+
+        match self.find_month(calendar, jd_date) {
+            Some(month) => Ok(month),
+            None => {
+                let months = calendar.system_at(jd).calculate_months(jd)?;
+                self.context = Some((calendar, months));
+                Ok(self.find_month(calendar, jd_date).expect("Should be found"))
+            }
+        }
+    }
+
+    /// Finds the month containing the date in the current months.
+    fn find_month(&self, calendar: Calendar, jd_date: f64) -> Option<TempoMonth> {
+        match &self.context {
+            Some((c, months)) if *c == calendar => months
+                .iter()
+                .find(|m| (m.jd_start..m.jd_end).contains(&jd_date))
+                .copied(),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,278 @@
+//! Contains response bodies shared by the endpoints.
+
+use anyhow::Result;
+use chrono::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    astro::{
+        julian::{date_of_julian_day, from_julian_date},
+        solver::RootDiagnostics,
+    },
+    cache_stats::CacheStats,
+    calendar::meridian_timezone,
+    diagnostics::ConversionDiagnostics,
+    locale::{Locale, Localize},
+    tempo::{RokuyoScheme, TempoDate},
+    verify::RoundTripViolation,
+    youbi::Youbi,
+};
+
+/// Represents `TempoDate`.
+#[derive(Debug, Clone, Serialize)]
+pub struct TempoDateResponse {
+    /// Lunar year, which changes at the 1st day of the 1st month.
+    pub year: usize,
+    /// Gregorian year of the date, which differs from `year` from January to the lunar new year.
+    pub gregory_year: i32,
+    pub month: usize,
+    pub day: usize,
+    pub leap_month: bool,
+    pub rokuyo_index: usize,
+    pub rokuyo_str: &'static str,
+    pub rokuyo_romaji: &'static str,
+    pub month_name: &'static str,
+    pub month_name_romaji: &'static str,
+}
+
+impl TempoDateResponse {
+    /// Constructs from the date with rokuyo in the scheme.
+    pub fn new(
+        tempo_date: &TempoDate,
+        rokuyo_scheme: RokuyoScheme,
+        locale: Locale,
+    ) -> Result<TempoDateResponse> {
+        let rokuyo = tempo_date.rokuyo(rokuyo_scheme);
+        let month_name = tempo_date.month_name();
+        Ok(TempoDateResponse {
+            year: tempo_date.year,
+            gregory_year: tempo_date.gregory_year()?,
+            month: tempo_date.month,
+            day: tempo_date.day,
+            leap_month: tempo_date.leap_month,
+            rokuyo_index: rokuyo.to_number(),
+            rokuyo_str: rokuyo.localize(locale),
+            rokuyo_romaji: rokuyo.to_romaji(),
+            month_name: month_name.localize(locale),
+            month_name_romaji: month_name.to_romaji(),
+        })
+    }
+}
+
+/// Represents a Gregorian date with its string in tempo calendar.
+#[derive(Debug, Clone, Serialize)]
+pub struct DateConversionResponse {
+    pub date: NaiveDate,
+    pub tempo_date_str: String,
+}
+
+impl DateConversionResponse {
+    /// Constructs from the converted date.
+    pub fn new(tempo_date: &TempoDate) -> Result<DateConversionResponse> {
+        Ok(DateConversionResponse {
+            date: date_of_julian_day(tempo_date.jd_date())?,
+            tempo_date_str: tempo_date.to_string(),
+        })
+    }
+}
+
+/// Represents `RoundTripViolation`.
+#[derive(Debug, Clone, Serialize)]
+pub struct RoundTripViolationResponse {
+    pub date: NaiveDate,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tempo_date_str: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub round_trip: Option<NaiveDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+impl RoundTripViolationResponse {
+    /// Constructs from the violation.
+    pub fn new(violation: &RoundTripViolation) -> RoundTripViolationResponse {
+        RoundTripViolationResponse {
+            date: violation.date,
+            tempo_date_str: violation.tempo_date.map(|d| d.to_string()),
+            round_trip: violation.round_trip,
+            error: violation.error.clone(),
+        }
+    }
+}
+
+/// Represents `Youbi`.
+#[derive(Debug, Clone, Serialize)]
+pub struct YoubiResponse {
+    pub str: &'static str,
+    pub kanji: &'static str,
+    pub romaji: &'static str,
+    pub english: &'static str,
+    pub iso_number: u32,
+}
+
+impl YoubiResponse {
+    /// Constructs from youbi.
+    pub fn new(youbi: Youbi, locale: Locale) -> YoubiResponse {
+        YoubiResponse {
+            str: youbi.localize(locale),
+            kanji: youbi.to_japanese(),
+            romaji: youbi.to_romaji(),
+            english: youbi.to_english(),
+            iso_number: youbi.to_iso_number(),
+        }
+    }
+}
+
+/// Represents a sekki on a date.
+#[derive(Debug, Clone, Serialize)]
+pub struct SekkiResponse {
+    pub name: &'static str,
+    pub name_romaji: &'static str,
+    pub instant: String,
+}
+
+/// Represents a root found by the solver.
+#[derive(Debug, Clone, Serialize)]
+pub struct RootResponse {
+    pub jd: f64,
+    pub instant: String,
+    pub iterations: usize,
+    pub residual: f64,
+}
+
+/// Represents a month in the diagnostics.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticsMonthResponse {
+    pub month: usize,
+    pub leap_month: bool,
+    pub first_date: NaiveDate,
+    pub days: usize,
+}
+
+/// Represents a sekki found by the solver.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticsSekkiResponse {
+    pub name: &'static str,
+    #[serde(flatten)]
+    pub root: RootResponse,
+}
+
+/// Represents a chuki which names months.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticsChukiResponse {
+    pub name: &'static str,
+    pub jd: f64,
+    pub instant: String,
+}
+
+/// Represents `ConversionDiagnostics`.
+#[derive(Debug, Clone, Serialize)]
+pub struct DiagnosticsResponse {
+    pub meridian_offset: f64,
+    pub months: Vec<DiagnosticsMonthResponse>,
+    pub sakus: Vec<RootResponse>,
+    pub sekkis: Vec<DiagnosticsSekkiResponse>,
+    pub chukis: Vec<DiagnosticsChukiResponse>,
+}
+
+impl DiagnosticsResponse {
+    /// Constructs from the diagnostics, with instants in the reference meridian.
+    pub fn new(diagnostics: &ConversionDiagnostics, locale: Locale) -> Result<DiagnosticsResponse> {
+        let offset = meridian_timezone(diagnostics.meridian_offset);
+        let local_instant = |jd: f64| -> Result<_> {
+            Ok(from_julian_date(jd)?
+                .with_timezone(&offset)
+                .to_rfc3339_opts(SecondsFormat::Millis, false))
+        };
+        let local_date = |jd: f64| -> Result<_> { Ok(from_julian_date(jd)?.date().naive_utc()) };
+        let root_response = |root: &RootDiagnostics| -> Result<_> {
+            Ok(RootResponse {
+                jd: root.root,
+                instant: local_instant(root.root)?,
+                iterations: root.iterations,
+                residual: root.residual,
+            })
+        };
+
+        let months = diagnostics
+            .months
+            .iter()
+            .map(|m| {
+                Ok(DiagnosticsMonthResponse {
+                    month: m.month,
+                    leap_month: m.leap_month,
+                    first_date: local_date(m.jd_start)?,
+                    days: m.days(),
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let sakus = diagnostics
+            .sakus
+            .iter()
+            .map(root_response)
+            .collect::<Result<Vec<_>>>()?;
+        let sekkis = diagnostics
+            .sekkis
+            .iter()
+            .map(|(root, sekki)| {
+                Ok(DiagnosticsSekkiResponse {
+                    name: sekki.localize(locale),
+                    root: root_response(root)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+        let chukis = diagnostics
+            .chukis
+            .iter()
+            .map(|(jd, sekki)| {
+                Ok(DiagnosticsChukiResponse {
+                    name: sekki.localize(locale),
+                    jd: *jd,
+                    instant: local_instant(*jd)?,
+                })
+            })
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(DiagnosticsResponse {
+            meridian_offset: diagnostics.meridian_offset,
+            months,
+            sakus,
+            sekkis,
+            chukis,
+        })
+    }
+}
+
+/// Represents `CacheStats`.
+#[derive(Debug, Clone, Serialize)]
+pub struct CacheStatsResponse {
+    pub hits: u64,
+    pub misses: u64,
+    pub hit_ratio: Option<f64>,
+    pub evictions: u64,
+    pub entries: usize,
+}
+
+impl CacheStatsResponse {
+    /// Constructs from the stats. The hit ratio is absent before any lookup.
+    pub fn new(stats: &CacheStats) -> CacheStatsResponse {
+        let lookups = stats.hits + stats.misses;
+        CacheStatsResponse {
+            hits: stats.hits,
+            misses: stats.misses,
+            hit_ratio: (lookups > 0).then(|| stats.hits as f64 / lookups as f64),
+            evictions: stats.evictions,
+            entries: stats.entries,
+        }
+    }
+}
+
+/// Represents an error.
+#[derive(Debug, Clone, Serialize)]
+pub struct ErrorResponse {
+    pub error: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accepted_formats: Option<&'static [&'static str]>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jd: Option<f64>,
+}
@@ -0,0 +1,277 @@
+//! Contains 24-sekki (二十四節気) definitions.
+
+use anyhow::{bail, Result};
+use chrono::prelude::*;
+
+use crate::{
+    astro::{
+        angle::Degree,
+        events::{sun_reaches, sun_reaches_with},
+        julian::{from_julian_date, to_julian_date},
+        longitude::{
+            jcg78::{sun_longitude, Jcg78},
+            LongitudeModel,
+        },
+    },
+    calendar::JST_OFFSET,
+    tempo::calculate_leading_24sekki,
+};
+
+/// Represents 24-sekki.
+/// Variants are ordered by the sun longitude, starting from shunbun (0 degree).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Sekki {
+    Shunbun,
+    Seimei,
+    Kokuu,
+    Rikka,
+    Shoman,
+    Boshu,
+    Geshi,
+    Shousho,
+    Taisho,
+    Risshu,
+    Shosho,
+    Hakuro,
+    Shubun,
+    Kanro,
+    Soko,
+    Ritto,
+    Shosetsu,
+    Taisetsu,
+    Toji,
+    Shokan,
+    Daikan,
+    Risshun,
+    Usui,
+    Keichitsu,
+}
+
+#[allow(dead_code)]
+impl Sekki {
+    /// Equinoxes and solstices in order of the sun longitude.
+    pub const EQUINOXES_AND_SOLSTICES: [Sekki; 4] =
+        [Sekki::Shunbun, Sekki::Geshi, Sekki::Shubun, Sekki::Toji];
+
+    /// Gets Japanese string.
+    pub fn to_japanese(self) -> &'static str {
+        match self {
+            Sekki::Shunbun => "春分",
+            Sekki::Seimei => "清明",
+            Sekki::Kokuu => "穀雨",
+            Sekki::Rikka => "立夏",
+            Sekki::Shoman => "小満",
+            Sekki::Boshu => "芒種",
+            Sekki::Geshi => "夏至",
+            Sekki::Shousho => "小暑",
+            Sekki::Taisho => "大暑",
+            Sekki::Risshu => "立秋",
+            Sekki::Shosho => "処暑",
+            Sekki::Hakuro => "白露",
+            Sekki::Shubun => "秋分",
+            Sekki::Kanro => "寒露",
+            Sekki::Soko => "霜降",
+            Sekki::Ritto => "立冬",
+            Sekki::Shosetsu => "小雪",
+            Sekki::Taisetsu => "大雪",
+            Sekki::Toji => "冬至",
+            Sekki::Shokan => "小寒",
+            Sekki::Daikan => "大寒",
+            Sekki::Risshun => "立春",
+            Sekki::Usui => "雨水",
+            Sekki::Keichitsu => "啓蟄",
+        }
+    }
+
+    /// Gets Japanese reading in hiragana.
+    pub fn to_reading(self) -> &'static str {
+        match self {
+            Sekki::Shunbun => "しゅんぶん",
+            Sekki::Seimei => "せいめい",
+            Sekki::Kokuu => "こくう",
+            Sekki::Rikka => "りっか",
+            Sekki::Shoman => "しょうまん",
+            Sekki::Boshu => "ぼうしゅ",
+            Sekki::Geshi => "げし",
+            Sekki::Shousho => "しょうしょ",
+            Sekki::Taisho => "たいしょ",
+            Sekki::Risshu => "りっしゅう",
+            Sekki::Shosho => "しょしょ",
+            Sekki::Hakuro => "はくろ",
+            Sekki::Shubun => "しゅうぶん",
+            Sekki::Kanro => "かんろ",
+            Sekki::Soko => "そうこう",
+            Sekki::Ritto => "りっとう",
+            Sekki::Shosetsu => "しょうせつ",
+            Sekki::Taisetsu => "たいせつ",
+            Sekki::Toji => "とうじ",
+            Sekki::Shokan => "しょうかん",
+            Sekki::Daikan => "だいかん",
+            Sekki::Risshun => "りっしゅん",
+            Sekki::Usui => "うすい",
+            Sekki::Keichitsu => "けいちつ",
+        }
+    }
+
+    /// Gets Hepburn romanization.
+    pub fn to_romaji(self) -> &'static str {
+        match self {
+            Sekki::Shunbun => "Shunbun",
+            Sekki::Seimei => "Seimei",
+            Sekki::Kokuu => "Kokuu",
+            Sekki::Rikka => "Rikka",
+            Sekki::Shoman => "Shōman",
+            Sekki::Boshu => "Bōshu",
+            Sekki::Geshi => "Geshi",
+            Sekki::Shousho => "Shōsho",
+            Sekki::Taisho => "Taisho",
+            Sekki::Risshu => "Risshū",
+            Sekki::Shosho => "Shosho",
+            Sekki::Hakuro => "Hakuro",
+            Sekki::Shubun => "Shūbun",
+            Sekki::Kanro => "Kanro",
+            Sekki::Soko => "Sōkō",
+            Sekki::Ritto => "Rittō",
+            Sekki::Shosetsu => "Shōsetsu",
+            Sekki::Taisetsu => "Taisetsu",
+            Sekki::Toji => "Tōji",
+            Sekki::Shokan => "Shōkan",
+            Sekki::Daikan => "Daikan",
+            Sekki::Risshun => "Risshun",
+            Sekki::Usui => "Usui",
+            Sekki::Keichitsu => "Keichitsu",
+        }
+    }
+
+    /// Gets English translation.
+    pub fn to_english(self) -> &'static str {
+        match self {
+            Sekki::Shunbun => "vernal equinox",
+            Sekki::Seimei => "clear and bright",
+            Sekki::Kokuu => "grain rain",
+            Sekki::Rikka => "beginning of summer",
+            Sekki::Shoman => "grain buds",
+            Sekki::Boshu => "grain in ear",
+            Sekki::Geshi => "summer solstice",
+            Sekki::Shousho => "minor heat",
+            Sekki::Taisho => "major heat",
+            Sekki::Risshu => "beginning of autumn",
+            Sekki::Shosho => "end of heat",
+            Sekki::Hakuro => "white dew",
+            Sekki::Shubun => "autumnal equinox",
+            Sekki::Kanro => "cold dew",
+            Sekki::Soko => "frost descent",
+            Sekki::Ritto => "beginning of winter",
+            Sekki::Shosetsu => "minor snow",
+            Sekki::Taisetsu => "major snow",
+            Sekki::Toji => "winter solstice",
+            Sekki::Shokan => "minor cold",
+            Sekki::Daikan => "major cold",
+            Sekki::Risshun => "beginning of spring",
+            Sekki::Usui => "rain water",
+            Sekki::Keichitsu => "awakening of insects",
+        }
+    }
+
+    /// Converts into numeral index, which is the sun longitude divided by 15.
+    pub fn to_number(self) -> usize {
+        self as usize
+    }
+
+    /// Converts from numeral index.
+    pub fn from_number(index: usize) -> Result<Sekki> {
+        const ALL: [Sekki; 24] = [
+            Sekki::Shunbun,
+            Sekki::Seimei,
+            Sekki::Kokuu,
+            Sekki::Rikka,
+            Sekki::Shoman,
+            Sekki::Boshu,
+            Sekki::Geshi,
+            Sekki::Shousho,
+            Sekki::Taisho,
+            Sekki::Risshu,
+            Sekki::Shosho,
+            Sekki::Hakuro,
+            Sekki::Shubun,
+            Sekki::Kanro,
+            Sekki::Soko,
+            Sekki::Ritto,
+            Sekki::Shosetsu,
+            Sekki::Taisetsu,
+            Sekki::Toji,
+            Sekki::Shokan,
+            Sekki::Daikan,
+            Sekki::Risshun,
+            Sekki::Usui,
+            Sekki::Keichitsu,
+        ];
+        match ALL.get(index) {
+            Some(sekki) => Ok(*sekki),
+            None => bail!("Out of sekki index"),
+        }
+    }
+
+    /// Gets the sekki which the sun longitude has most recently passed.
+    pub fn from_longitude(longitude: Degree) -> Sekki {
+        let index = (longitude.normalized().0 / 15.0).floor() as usize;
+        Sekki::from_number(index % 24).expect("Should be rounded by 24")
+    }
+
+    /// Gets the target sun longitude.
+    pub fn longitude(self) -> Degree {
+        Degree(self.to_number() as f64 * 15.0)
+    }
+
+    /// Whether this is chuki (中気).
+    pub fn is_chuki(self) -> bool {
+        self.to_number() & 1 == 0
+    }
+
+    /// Gets the month number which is named by this chuki.
+    pub fn chuki_month(self) -> usize {
+        match self.to_number() / 2 {
+            0 => 2,
+            3 => 5,
+            6 => 8,
+            9 => 11,
+            otherwise => (otherwise + 1) % 12 + 1,
+        }
+    }
+
+    /// Calculates the next sekki and its instant after the datetime.
+    pub fn next_after<Tz: TimeZone>(datetime: &DateTime<Tz>) -> Result<(Sekki, DateTime<Utc>)> {
+        let jd = to_julian_date(datetime);
+        let l_sun = Degree(sun_longitude(jd));
+        let next = Sekki::from_number((Sekki::from_longitude(l_sun).to_number() + 1) % 24)
+            .expect("Should be rounded by 24");
+
+        let delta_l = next.longitude().ahead_of(l_sun);
+        let jd_next = sun_reaches(next.longitude(), jd + delta_l.0 * 365.2 / 360.0)?;
+        Ok((next, from_julian_date(jd_next)?))
+    }
+
+    /// Finds the sekki whose instant falls on the JST date.
+    pub fn on_jst_date(date: NaiveDate) -> Result<Option<(Sekki, DateTime<Utc>)>> {
+        let jd_start = to_julian_date(&Utc.from_utc_date(&date).and_hms(0, 0, 0)) - JST_OFFSET;
+        let (jd, sekki) = calculate_leading_24sekki(jd_start + 1.0)?;
+        if (jd_start..jd_start + 1.0).contains(&jd) {
+            Ok(Some((sekki, from_julian_date(jd)?)))
+        } else {
+            Ok(None)
+        }
+    }
+
+    /// Calculates the instant of this sekki in the Gregorian year in the model, with Julian Date.
+    pub fn jd_in_year<M: LongitudeModel + ?Sized>(self, year: i32, model: &M) -> Result<f64> {
+        // Shunbun is around 3/20, the 79th day of the year
+        let days = (79.0 + self.longitude().0 * 365.2 / 360.0) % 365.0;
+        let jd_guess = to_julian_date(&Utc.ymd(year, 1, 1).and_hms(0, 0, 0)) + days;
+        sun_reaches_with(self.longitude(), jd_guess, model)
+    }
+
+    /// Calculates the instant of this sekki in the Gregorian year, including the sub-second part.
+    pub fn instant_in_year(self, year: i32) -> Result<DateTime<Utc>> {
+        from_julian_date(self.jd_in_year(year, &Jcg78)?)
+    }
+}
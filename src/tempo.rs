@@ -1,15 +1,43 @@
-use std::fmt::{Display, Formatter, Result as FmtResult};
+use std::{
+    collections::{BTreeMap, VecDeque},
+    fmt::{Display, Formatter, Result as FmtResult},
+    iter::successors,
+    sync::{Arc, Mutex, RwLock},
+};
 
 use anyhow::{bail, Result};
 use chrono::prelude::*;
+use serde::Deserialize;
 
-use crate::astro::{
-    julian::{from_julian_date, to_julian_date},
-    longitude::jcg78::{moon_longitude, sun_longitude},
+use crate::{
+    astro::{
+        angle::Degree,
+        events::sun_reaches,
+        julian::{date_of_julian_day, julian_day_of},
+        longitude::{
+            jcg78::{
+                ensure_supported_date, ensure_supported_year, moon_longitude, sun_longitude, Jcg78,
+            },
+            LongitudeModel,
+        },
+        moon::{self, SYNODIC_MONTH},
+        solver::{default_tolerance, diagnose_root_refined, diagnose_root_within, RootDiagnostics},
+    },
+    budget,
+    cache_stats::{CacheCounters, CacheStats},
+    calendar::{
+        local_day, Calendar, CalendarSystem, DayBoundary, TermMethod, JST_OFFSET, SUI_SPAN,
+    },
+    error::ConversionError,
+    event_table,
+    kanji::{to_kanji, KanjiStyle},
+    sekki::Sekki,
+    youbi::Youbi,
 };
 
 /// Represents rokuyo.
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum Rokuyo {
     Taian,
     Shakku,
@@ -33,6 +61,30 @@ impl Rokuyo {
         }
     }
 
+    /// Gets Hepburn romanization.
+    pub fn to_romaji(self) -> &'static str {
+        match self {
+            Rokuyo::Taian => "Taian",
+            Rokuyo::Shakku => "Shakku",
+            Rokuyo::Sensho => "Senshō",
+            Rokuyo::Tomobiki => "Tomobiki",
+            Rokuyo::Sempu => "Senpu",
+            Rokuyo::Butsumetsu => "Butsumetsu",
+        }
+    }
+
+    /// Gets English translation.
+    pub fn to_english(self) -> &'static str {
+        match self {
+            Rokuyo::Taian => "great peace",
+            Rokuyo::Shakku => "red mouth",
+            Rokuyo::Sensho => "early win",
+            Rokuyo::Tomobiki => "friend pulling",
+            Rokuyo::Sempu => "early loss",
+            Rokuyo::Butsumetsu => "death of Buddha",
+        }
+    }
+
     /// Converts into numeral index.
     pub fn to_number(self) -> usize {
         match self {
@@ -59,9 +111,209 @@ impl Rokuyo {
     }
 }
 
+/// Represents a rule of rokuyo assignment.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RokuyoScheme {
+    /// Modern rule; 1/1 is sensho and it advances by month and day.
+    #[default]
+    Standard,
+
+    /// Koumei rokuyo-sei (孔明六曜星) in the order of shorokujin (小六壬);
+    /// 1/1 is taian, followed by ryuren (tomobiki), sokki (sensho), shakku, shokichi (sempu)
+    /// and kumo (butsumetsu).
+    Koumei,
+}
+
+impl RokuyoScheme {
+    /// Gets the rokuyo of the month and day.
+    pub fn rokuyo(self, month: usize, day: usize) -> Rokuyo {
+        let index = (month + day - 2) % 6;
+        match self {
+            RokuyoScheme::Standard => Rokuyo::from_number(index).expect("Should be rounded by 6"),
+            RokuyoScheme::Koumei => match index {
+                0 => Rokuyo::Taian,
+                1 => Rokuyo::Tomobiki,
+                2 => Rokuyo::Sensho,
+                3 => Rokuyo::Shakku,
+                4 => Rokuyo::Sempu,
+                _ => Rokuyo::Butsumetsu,
+            },
+        }
+    }
+}
+
+/// Represents shio (潮名), the tide name.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Shio {
+    Oshio,
+    Nakashio,
+    Koshio,
+    Nagashio,
+    Wakashio,
+}
+
+impl Shio {
+    /// Gets Japanese string.
+    pub fn to_japanese(self) -> &'static str {
+        match self {
+            Shio::Oshio => "大潮",
+            Shio::Nakashio => "中潮",
+            Shio::Koshio => "小潮",
+            Shio::Nagashio => "長潮",
+            Shio::Wakashio => "若潮",
+        }
+    }
+
+    /// Gets Hepburn romanization.
+    pub fn to_romaji(self) -> &'static str {
+        match self {
+            Shio::Oshio => "Ōshio",
+            Shio::Nakashio => "Nakashio",
+            Shio::Koshio => "Koshio",
+            Shio::Nagashio => "Nagashio",
+            Shio::Wakashio => "Wakashio",
+        }
+    }
+
+    /// Gets English translation.
+    pub fn to_english(self) -> &'static str {
+        match self {
+            Shio::Oshio => "spring tide",
+            Shio::Nakashio => "middle tide",
+            Shio::Koshio => "neap tide",
+            Shio::Nagashio => "long tide",
+            Shio::Wakashio => "young tide",
+        }
+    }
+
+    /// Classifies from lunar age in days.
+    pub fn from_lunar_age(age: f64) -> Shio {
+        match age.floor() as usize % 30 {
+            0..=2 | 14..=17 | 29 => Shio::Oshio,
+            3..=6 | 12..=13 | 18..=21 | 27..=28 => Shio::Nakashio,
+            7..=9 | 22..=24 => Shio::Koshio,
+            10 | 25 => Shio::Nagashio,
+            _ => Shio::Wakashio,
+        }
+    }
+}
+
+/// Represents traditional month names (和風月名).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum MonthName {
+    Mutsuki,
+    Kisaragi,
+    Yayoi,
+    Uzuki,
+    Satsuki,
+    Minazuki,
+    Fumizuki,
+    Hazuki,
+    Nagatsuki,
+    Kannazuki,
+    Shimotsuki,
+    Shiwasu,
+}
+
+#[allow(dead_code)]
+impl MonthName {
+    /// Gets Japanese string.
+    pub fn to_japanese(self) -> &'static str {
+        match self {
+            MonthName::Mutsuki => "睦月",
+            MonthName::Kisaragi => "如月",
+            MonthName::Yayoi => "弥生",
+            MonthName::Uzuki => "卯月",
+            MonthName::Satsuki => "皐月",
+            MonthName::Minazuki => "水無月",
+            MonthName::Fumizuki => "文月",
+            MonthName::Hazuki => "葉月",
+            MonthName::Nagatsuki => "長月",
+            MonthName::Kannazuki => "神無月",
+            MonthName::Shimotsuki => "霜月",
+            MonthName::Shiwasu => "師走",
+        }
+    }
+
+    /// Gets Japanese reading in hiragana.
+    pub fn to_reading(self) -> &'static str {
+        match self {
+            MonthName::Mutsuki => "むつき",
+            MonthName::Kisaragi => "きさらぎ",
+            MonthName::Yayoi => "やよい",
+            MonthName::Uzuki => "うづき",
+            MonthName::Satsuki => "さつき",
+            MonthName::Minazuki => "みなづき",
+            MonthName::Fumizuki => "ふみづき",
+            MonthName::Hazuki => "はづき",
+            MonthName::Nagatsuki => "ながつき",
+            MonthName::Kannazuki => "かんなづき",
+            MonthName::Shimotsuki => "しもつき",
+            MonthName::Shiwasu => "しわす",
+        }
+    }
+
+    /// Gets Hepburn romanization.
+    pub fn to_romaji(self) -> &'static str {
+        match self {
+            MonthName::Mutsuki => "Mutsuki",
+            MonthName::Kisaragi => "Kisaragi",
+            MonthName::Yayoi => "Yayoi",
+            MonthName::Uzuki => "Uzuki",
+            MonthName::Satsuki => "Satsuki",
+            MonthName::Minazuki => "Minazuki",
+            MonthName::Fumizuki => "Fumizuki",
+            MonthName::Hazuki => "Hazuki",
+            MonthName::Nagatsuki => "Nagatsuki",
+            MonthName::Kannazuki => "Kannazuki",
+            MonthName::Shimotsuki => "Shimotsuki",
+            MonthName::Shiwasu => "Shiwasu",
+        }
+    }
+
+    /// Gets English translation.
+    pub fn to_english(self) -> &'static str {
+        match self {
+            MonthName::Mutsuki => "month of harmony",
+            MonthName::Kisaragi => "month of layered clothes",
+            MonthName::Yayoi => "month of growth",
+            MonthName::Uzuki => "month of deutzia",
+            MonthName::Satsuki => "month of rice planting",
+            MonthName::Minazuki => "month of water",
+            MonthName::Fumizuki => "month of letters",
+            MonthName::Hazuki => "month of leaves",
+            MonthName::Nagatsuki => "month of long nights",
+            MonthName::Kannazuki => "month of gods",
+            MonthName::Shimotsuki => "month of frost",
+            MonthName::Shiwasu => "month of running priests",
+        }
+    }
+
+    /// Gets the name of the month number.
+    pub fn from_month(month: usize) -> Result<MonthName> {
+        match month {
+            1 => Ok(MonthName::Mutsuki),
+            2 => Ok(MonthName::Kisaragi),
+            3 => Ok(MonthName::Yayoi),
+            4 => Ok(MonthName::Uzuki),
+            5 => Ok(MonthName::Satsuki),
+            6 => Ok(MonthName::Minazuki),
+            7 => Ok(MonthName::Fumizuki),
+            8 => Ok(MonthName::Hazuki),
+            9 => Ok(MonthName::Nagatsuki),
+            10 => Ok(MonthName::Kannazuki),
+            11 => Ok(MonthName::Shimotsuki),
+            12 => Ok(MonthName::Shiwasu),
+            _ => bail!("Out of month number"),
+        }
+    }
+}
+
 /// Represents a tempo calendar date.
 #[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
 pub struct TempoDate {
+    /// Lunar year, counted from the 1st month. See `TempoMonth::lunar_year`.
     pub year: usize,
     pub leap_month: bool,
     pub month: usize,
@@ -93,182 +345,818 @@ impl Display for TempoDate {
     }
 }
 
-impl TempoDate {
-    /// Converts into tempo calendar date.
-    pub fn from_gregory_date<Tz: TimeZone>(jst_date: Date<Tz>) -> Result<TempoDate> {
-        let jd = to_julian_date(&jst_date.and_hms(0, 0, 0));
-        let jd_date = to_julian_date(&from_julian_date(jd + 0.375).date().and_hms(0, 0, 0));
+/// Tempo calendar (天保暦), which uses true sekkis (定気法) in JST.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TempoCalendar {
+    /// Definition of the day boundary, JST midnight in the standard.
+    pub day_boundary: DayBoundary,
 
-        // 1. Calculate 24-sekkis -------------------------------------------------
+    /// Offset of the reference meridian from UTC in days, JST in the standard.
+    pub meridian_offset: f64,
 
-        // 1-a. Start from current date
-        let mut sekkis = vec![];
-        let mut last_sekki = calculate_leading_24sekki(jd);
-        sekkis.push(last_sekki);
+    /// Method to place chukis, true terms in the standard.
+    pub term_method: TermMethod,
+}
 
-        // 1-b. Calculate 24-sekkis back to last toji
-        while last_sekki.1 as usize / 15 != 18 {
-            // Why 13.0? It could be 1.0.
-            let prev_sekki = calculate_leading_24sekki(last_sekki.0 - 13.0);
-            sekkis.insert(0, prev_sekki);
-            last_sekki = prev_sekki;
-        }
+/// Tempo calendar system.
+pub const TEMPO: TempoCalendar = TempoCalendar {
+    day_boundary: DayBoundary::Standard,
+    meridian_offset: JST_OFFSET,
+    term_method: TermMethod::True,
+};
 
-        // 1-c. Calculate 24-sekkis forward to next usui
-        last_sekki = *sekkis.last().expect("Should be have at 1 element");
-        while last_sekki.1 as usize / 15 != 22 {
-            // Why 18.0?
-            let next_sekki = calculate_leading_24sekki(last_sekki.0 + 18.0);
-            sekkis.push(next_sekki);
-            last_sekki = next_sekki;
-        }
+impl CalendarSystem for TempoCalendar {
+    fn meridian_offset(&self) -> f64 {
+        self.meridian_offset
+    }
 
-        // 2. Calculate sakus -----------------------------------------------------
+    fn local_instant(&self, jd: f64) -> f64 {
+        self.day_boundary.local_instant(jd, self.meridian_offset())
+    }
 
-        // 2-a. Start from current date
-        let mut sakus = vec![];
-        let mut last_saku = calculate_leading_saku(jd)?;
-        sakus.push(last_saku);
+    fn chukis(&self, jd_from: f64, jd_to: f64) -> Result<Vec<(f64, Sekki)>> {
+        match self.term_method {
+            TermMethod::True => calculate_true_chukis(jd_from, jd_to),
+            TermMethod::Mean => calculate_mean_chukis(jd_from, jd_to),
+        }
+    }
 
-        // 2-b. Calculate sakus back to last toji
-        let jd_toji = sekkis.first().expect("Should have 24 elements").0;
-        while last_saku > jd_toji {
-            let prev_saku = calculate_leading_saku(last_saku - 27.0)?;
-            sakus.insert(0, prev_saku);
-            last_saku = prev_saku;
-        }
-
-        // 2-c. Calculate sakus forward to next usui
-        last_saku = *sakus.last().expect("Should be have at 1 element");
-        let jd_usui = sekkis.last().expect("Should have 24 elements").0;
-        while last_saku < jd_usui {
-            let mut next_saku = calculate_leading_saku(last_saku + 30.0)?;
-            if (next_saku - last_saku).abs() < 26.0 {
-                next_saku = calculate_leading_saku(last_saku + 35.0)?;
+    fn calculate_months(&self, jd: f64) -> Result<Vec<TempoMonth>> {
+        // Only the standard system is cached
+        if *self == TEMPO {
+            let cached = cached_tempo_months(jd)?;
+            YEAR_MONTHS_COUNTERS.record(cached.is_some());
+            if let Some(tempo_months) = cached {
+                return Ok(tempo_months);
             }
-            sakus.push(next_saku);
-            last_saku = next_saku;
         }
+        calculate_month_window(jd, self)
+    }
+}
 
-        // 3. Correspond chuki and sakus ------------------------------------------
-        let chukis: Vec<_> = sekkis
-            .iter()
-            .filter(|x| x.1 as usize % 30 == 0)
-            .copied()
-            .collect();
-        let mut tempo_months = vec![TempoDate::default(); sakus.len() - 1];
-        for (saku, tempo) in sakus.windows(2).zip(&mut tempo_months) {
-            let (saku_start, saku_end) = (
-                from_julian_date(saku[0] + 0.375).date(),
-                from_julian_date(saku[1] + 0.375).date(),
-            );
-
-            let corresponding_chuki = chukis.iter().find(|chuki| {
-                let chuki_date = from_julian_date(chuki.0 + 0.375).date();
-                (saku_start..saku_end).contains(&chuki_date)
-            });
-            match corresponding_chuki {
-                Some((_, l)) => {
-                    tempo.month = match *l as usize / 30 {
-                        0 => 2,
-                        3 => 5,
-                        6 => 8,
-                        9 => 11,
-                        otherwise => (otherwise + 1) % 12 + 1,
-                    };
-                    tempo.leap_month = false;
-                    tempo.jd = to_julian_date(&saku_start.and_hms(0, 0, 0));
-                }
-                None => {
-                    tempo.month = 0;
-                    tempo.leap_month = true;
-                    tempo.jd = to_julian_date(&saku_start.and_hms(0, 0, 0));
-                }
-            }
+/// Represents a month in tempo calendar.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd)]
+pub struct TempoMonth {
+    pub leap_month: bool,
+    pub month: usize,
+    /// JD of the first day (JST date as UTC midnight).
+    pub jd_start: f64,
+    /// JD of the first day of the next month.
+    pub jd_end: f64,
+}
+
+impl TempoMonth {
+    /// Gets the number of days in this month.
+    pub fn days(&self) -> usize {
+        (self.jd_end - self.jd_start).round() as usize
+    }
+
+    /// Gets the lunar year, which is the Gregorian year when the 1st month of the year starts.
+    pub fn lunar_year(&self) -> Result<i32> {
+        // The 1st month contains usui around February 19, so it starts from January 20 to February 19.
+        // Months starting earlier in the Gregorian year are the 12th month (or a leap 11th or 12th)
+        // of the previous lunar year, while the 11th month contains toji and never starts in January.
+        let start = date_of_julian_day(self.jd_start)?;
+        if self.month >= 11 && start.month() <= 2 {
+            Ok(start.year() - 1)
+        } else {
+            Ok(start.year())
         }
+    }
+}
 
-        for i in 1..(tempo_months.len()) {
-            if tempo_months[i].leap_month {
-                tempo_months[i].month = tempo_months[i - 1].month;
+impl TempoDate {
+    /// Converts into tempo calendar date.
+    pub fn from_gregory_date<Tz: TimeZone>(jst_date: Date<Tz>) -> Result<TempoDate> {
+        TempoDate::from_gregory_date_with(jst_date, &TEMPO)
+    }
+
+    /// Converts into the date in the selected calendar.
+    pub fn from_gregory_date_in<Tz: TimeZone>(
+        jst_date: Date<Tz>,
+        calendar: Calendar,
+    ) -> Result<TempoDate> {
+        let jd = julian_day_of(jst_date.naive_local()) - JST_OFFSET;
+        TempoDate::from_gregory_date_with(jst_date, calendar.system_at(jd))
+    }
+
+    /// Converts into the date in the selected calendar, with the day boundary and the term method.
+    /// Boundaries and methods other than the standard are research options of tempo calendar rules.
+    pub fn from_gregory_date_bounded<Tz: TimeZone>(
+        jst_date: Date<Tz>,
+        calendar: Calendar,
+        day_boundary: DayBoundary,
+        term_method: TermMethod,
+    ) -> Result<TempoDate> {
+        match (calendar, day_boundary, term_method) {
+            (_, DayBoundary::Standard, TermMethod::True) => {
+                TempoDate::from_gregory_date_in(jst_date, calendar)
             }
+            (Calendar::Tempo, _, _) => {
+                let calendar = TempoCalendar {
+                    day_boundary,
+                    term_method,
+                    ..TEMPO
+                };
+                TempoDate::from_gregory_date_with(jst_date, &calendar)
+            }
+            _ => Err(ConversionError::UnsupportedOptions {
+                requested: format!(
+                    "Day boundary {:?} with {:?} terms in {:?} calendar",
+                    day_boundary, term_method, calendar
+                ),
+            }
+            .into()),
         }
+    }
+
+    /// Converts into the date in the calendar system.
+    pub fn from_gregory_date_with<Tz: TimeZone, C: CalendarSystem + ?Sized>(
+        jst_date: Date<Tz>,
+        calendar: &C,
+    ) -> Result<TempoDate> {
+        ensure_supported_date(jst_date.naive_local())?;
+        let jd_date = julian_day_of(jst_date.naive_local());
+        let jd = jd_date - calendar.meridian_offset();
 
+        let tempo_months = calendar.calculate_months(jd)?;
         let target_month = tempo_months
             .iter()
-            .filter(|m| jd_date >= m.jd)
-            .last()
+            .rev()
+            .find(|m| jd_date >= m.jd_start)
             .expect("Should be found");
-        let mut tempo_date = *target_month;
-        tempo_date.day = (jd_date - tempo_date.jd) as usize + 1;
-        tempo_date.year = match jst_date.year() {
-            y if tempo_date.month >= 10 && tempo_date.month > jst_date.month() as usize => {
-                y as usize - 1
-            }
-            otherwise => otherwise as usize,
+        let mut tempo_date = TempoDate {
+            month: target_month.month,
+            leap_month: target_month.leap_month,
+            jd: target_month.jd_start,
+            ..Default::default()
         };
+        tempo_date.day = (jd_date - tempo_date.jd) as usize + 1;
+        tempo_date.year = target_month.lunar_year()? as usize;
         Ok(tempo_date)
     }
 
-    /// Gets rokuyo.
-    pub fn rokuyo(&self) -> Rokuyo {
-        Rokuyo::from_number((self.month + self.day - 2) % 6).expect("Should be rounded by 6")
+    /// Converts into Gregory date in JST.
+    /// Fails if the date does not exist in the calendar.
+    /// This is the inverse of `from_gregory_date` over the supported range, verified by `verify`.
+    pub fn to_gregory_date(self) -> Result<NaiveDate> {
+        ensure_supported_year(self.year as i32)?;
+
+        // Months after toji are converted with the months of the next year, which can name them
+        // differently, and some years lack the 1st month, so candidates are converted back to check.
+        let mut windows = vec![cached_year_months(self.year as i32)?];
+        if ensure_supported_year(self.year as i32 + 1).is_ok() {
+            windows.push(cached_year_months(self.year as i32 + 1)?);
+        }
+        let candidates = windows.iter().flat_map(|w| w.months.iter()).filter(|m| {
+            m.month == self.month
+                && m.leap_month == self.leap_month
+                && (1..=m.days()).contains(&self.day)
+        });
+        for month in candidates {
+            let date = date_of_julian_day(month.jd_start + (self.day - 1) as f64)?;
+            let converted = TempoDate::from_gregory_date(Utc.from_utc_date(&date))?;
+            if (
+                converted.year,
+                converted.month,
+                converted.leap_month,
+                converted.day,
+            ) == (self.year, self.month, self.leap_month, self.day)
+            {
+                return Ok(date);
+            }
+        }
+        Err(ConversionError::NonexistentDate {
+            requested: self.to_string(),
+        }
+        .into())
+    }
+
+    /// Formats in kanji numerals, like 二千二十四年閏二月十一日.
+    pub fn to_kanji_string(self, style: KanjiStyle) -> String {
+        format!(
+            "{}年{}{}月{}日",
+            to_kanji(self.year, style),
+            if self.leap_month { "閏" } else { "" },
+            to_kanji(self.month, style),
+            to_kanji(self.day, style)
+        )
+    }
+
+    /// Gets JD of this date (JST date as UTC midnight).
+    pub fn jd_date(&self) -> f64 {
+        self.jd + (self.day - 1) as f64
+    }
+
+    /// Gets the Gregorian year of this date, which may differ from the lunar year.
+    pub fn gregory_year(&self) -> Result<i32> {
+        Ok(date_of_julian_day(self.jd_date())?.year())
+    }
+
+    /// Calculates the number of days until the other date.
+    /// Both dates must be converted from Gregory date so that they have JD.
+    pub fn days_until(&self, other: &TempoDate) -> i64 {
+        (other.jd_date() - self.jd_date()).round() as i64
+    }
+
+    /// Calculates the number of months until the month of the other date, counting leap months.
+    /// Both dates must be converted from Gregory date so that they have JD.
+    pub fn months_until(&self, other: &TempoDate) -> i64 {
+        // Deviation of month lengths from the mean synodic month never accumulates over a day
+        ((other.jd - self.jd) / 29.530589).round() as i64
+    }
+
+    /// Calculates the 1-based day index in the lunar year and the number of days of the year
+    /// in the calendar system, which must be the one this date is converted with.
+    pub fn ordinal<C: CalendarSystem + ?Sized>(&self, calendar: &C) -> Result<(usize, usize)> {
+        let jd_first = lunar_new_year_day(self.year, calendar)?;
+        let jd_next = lunar_new_year_day(self.year + 1, calendar)?;
+
+        let offset = (self.jd_date() - jd_first).round() as i64;
+        let days = (jd_next - jd_first).round() as i64;
+        if !(0..days).contains(&offset) {
+            return Err(ConversionError::NonexistentDate {
+                requested: self.to_string(),
+            }
+            .into());
+        }
+        Ok((offset as usize + 1, days as usize))
+    }
+
+    /// Gets youbi.
+    pub fn youbi(&self) -> Result<Youbi> {
+        Ok(Youbi::of_day(date_of_julian_day(self.jd_date())?))
+    }
+
+    /// Gets rokuyo in the scheme.
+    pub fn rokuyo(&self, scheme: RokuyoScheme) -> Rokuyo {
+        scheme.rokuyo(self.month, self.day)
+    }
+
+    /// Gets the traditional month name.
+    pub fn month_name(&self) -> MonthName {
+        MonthName::from_month(self.month).expect("Should be in 1 to 12")
+    }
+
+    /// Calculates lunar age at noon in JST.
+    pub fn moon_age(&self) -> Result<f64> {
+        moon::age(self.jd_date() + 0.5 - JST_OFFSET)
+    }
+
+    /// Calculates the illuminated fraction of the moon at noon in JST.
+    pub fn moon_illumination(&self) -> f64 {
+        moon::illumination(self.jd_date() + 0.5 - JST_OFFSET)
+    }
+
+    /// Gets shio by lunar age at noon in JST.
+    pub fn shio(&self) -> Result<Shio> {
+        Ok(Shio::from_lunar_age(self.moon_age()?))
     }
 }
 
-/// Calculates leading 24-sekki with Julian Date.
-pub fn calculate_leading_24sekki(jd_now: f64) -> (f64, f64) {
-    let l_sun_now = sun_longitude(jd_now);
-    let l_sun0 = 15.0 * (l_sun_now / 15.0).floor();
+/// Calculates tempo months in the lunar year, from the 1st month to the 12th month
+/// (or its leap month).
+pub fn calculate_lunar_year_months(year: usize) -> Result<Vec<TempoMonth>> {
+    ensure_supported_year(year as i32)?;
 
-    let mut delta_t = 1.0f64;
-    let mut jd = jd_now;
-    while delta_t.abs() > (1.0 / 86400.0) {
-        let l_sun = sun_longitude(jd);
-        let delta_l = match l_sun - l_sun0 {
-            x if x > 180.0 => x - 360.0,
-            x if x < -180.0 => x + 360.0,
-            otherwise => otherwise,
-        };
+    // Months from the previous 11th month to the next 1st month are calculated
+    let year_months = cached_year_months(year as i32)?;
+    let tempo_months = &year_months.months;
 
-        delta_t = delta_l * 365.2 / 360.0;
-        jd -= delta_t;
+    let first_month_index = tempo_months
+        .iter()
+        .position(|m| m.month == 1 && !m.leap_month)
+        .expect("Should contain 1st month");
+    let year_months = tempo_months[first_month_index..]
+        .iter()
+        .enumerate()
+        .take_while(|(i, m)| *i == 0 || m.month != 1 || m.leap_month)
+        .map(|(_, m)| *m)
+        .collect();
+    Ok(year_months)
+}
+
+/// Calculates the first day of the lunar year in the calendar system
+/// (local date as UTC midnight).
+fn lunar_new_year_day<C: CalendarSystem + ?Sized>(year: usize, calendar: &C) -> Result<f64> {
+    ensure_supported_year(year as i32)?;
+
+    // The 1st month starts from January 20 to February 20, so months around February 5 contain it
+    // along with the 1st months of the adjacent years
+    let jd_date = julian_day_of(NaiveDate::from_ymd(year as i32, 2, 5));
+    let tempo_months = calendar.calculate_months(jd_date - calendar.meridian_offset())?;
+    for month in tempo_months
+        .iter()
+        .filter(|m| m.month == 1 && !m.leap_month)
+    {
+        if month.lunar_year()? == year as i32 {
+            return Ok(month.jd_start);
+        }
+    }
+    unreachable!("Should contain 1st month")
+}
+
+/// Gets the leap month of the lunar year if any.
+pub fn leap_month_of_year(year: usize) -> Result<Option<usize>> {
+    let tempo_months = calculate_lunar_year_months(year)?;
+    Ok(tempo_months.iter().find(|m| m.leap_month).map(|m| m.month))
+}
+
+/// Whether the lunar year has a leap month.
+pub fn is_leap_lunar_year(year: usize) -> Result<bool> {
+    Ok(leap_month_of_year(year)?.is_some())
+}
+
+/// Tempo months from the toji in the previous year to the toji in the next year, keyed by the year.
+static YEAR_MONTHS: RwLock<YearMonthsCache> = RwLock::new(YearMonthsCache::new());
+
+/// Maximum count of cached years, which covers from Jokyo calendar to the end of the embedded event table.
+const MAX_CACHED_YEARS: usize = 512;
+
+/// Locks of the years being calculated, so that concurrent requests calculate each year once.
+static YEAR_MONTHS_IN_FLIGHT: Mutex<BTreeMap<i32, Arc<Mutex<()>>>> = Mutex::new(BTreeMap::new());
+
+/// Lookups of the year months, both by years and by instants.
+static YEAR_MONTHS_COUNTERS: CacheCounters = CacheCounters::new();
+
+/// Index of the next toji in the sekkis from a toji, as a year has 24 of them.
+const NEXT_TOJI_INDEX: usize = 24;
+
+/// Maximum count of iterations to find a bochi, which converges in a few of them.
+const MAX_BOCHI_ITERATIONS: usize = 20;
+
+/// Represents cached year months keyed by the year, evicted in the order of insertion.
+/// Entries are shared so that cache hits do not copy the events.
+#[derive(Debug, Clone, PartialEq)]
+struct YearMonthsCache {
+    entries: BTreeMap<i32, Arc<YearMonths>>,
+    order: VecDeque<i32>,
+}
+
+impl YearMonthsCache {
+    /// Constructs an empty cache.
+    const fn new() -> YearMonthsCache {
+        YearMonthsCache {
+            entries: BTreeMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Gets the months of the year.
+    fn get(&self, year: &i32) -> Option<&Arc<YearMonths>> {
+        self.entries.get(year)
+    }
+
+    /// Inserts the months of the year, returning the count of evicted years.
+    fn insert(&mut self, year: i32, year_months: Arc<YearMonths>) -> u64 {
+        if self.entries.insert(year, year_months).is_some() {
+            return 0;
+        }
+        self.order.push_back(year);
+
+        let mut evicted = 0;
+        while self.order.len() > MAX_CACHED_YEARS {
+            let oldest = self.order.pop_front().expect("Should have years");
+            self.entries.remove(&oldest);
+            evicted += 1;
+        }
+        evicted
+    }
+
+    /// Gets the count of cached years.
+    fn len(&self) -> usize {
+        self.entries.len()
+    }
+}
+
+/// Represents tempo months from a toji to the toji after the next toji, with the events naming them.
+#[derive(Debug, Clone, PartialEq)]
+struct YearMonths {
+    /// 24-sekkis from the toji to the last toji.
+    sekkis: Vec<(f64, Sekki)>,
+
+    /// Sakus from the one before the toji to the second one after the last toji.
+    sakus: Vec<f64>,
+
+    /// Tempo months named from them.
+    months: Vec<TempoMonth>,
+}
+
+impl YearMonths {
+    /// Calculates the months from the last toji before the instant.
+    fn calculate(jd: f64, calendar: &TempoCalendar) -> Result<YearMonths> {
+        // 1. Calculate 24-sekkis ---------------------------------------------
+
+        // 1-a. Calculate 24-sekkis from current date back to last toji, in reverse order
+        let mut sekkis = vec![];
+        for sekki in preceding_24sekkis(jd) {
+            let sekki = sekki?;
+            sekkis.push(sekki);
+            if sekki.1 == Sekki::Toji {
+                break;
+            }
+        }
+        sekkis.reverse();
+
+        // 1-b. Calculate 24-sekkis forward to the toji after next
+        extend_sekkis_to_toji(&mut sekkis)?;
+
+        // 2. Calculate sakus -------------------------------------------------
+
+        // 2-a. Enumerate sakus around the range of sekkis
+        let jd_toji = sekkis.first().expect("Should have 24 elements").0;
+        let jd_last_toji = sekkis.last().expect("Should have 24 elements").0;
+        let mut sakus = calculate_sakus(
+            jd_toji - SYNODIC_MONTH - 1.0,
+            jd_last_toji + 2.0 * SYNODIC_MONTH + 1.0,
+        )?;
+
+        // 2-b. Keep from the saku before the toji to the second saku after the last toji,
+        // as the last toji can be on the day of the saku after it
+        let first = saku_before(&sakus, jd_toji);
+        let last = saku_after(&sakus, jd_last_toji) + 1;
+        sakus.truncate(last + 1);
+        sakus.drain(..first);
+
+        Ok(YearMonths::name(sekkis, sakus, calendar))
+    }
+
+    /// Calculates the months from the next toji.
+    /// Sekkis and sakus up to the last toji are reused, so only those in the following year are calculated.
+    fn calculate_next(&self, calendar: &TempoCalendar) -> Result<YearMonths> {
+        // 1. Keep 24-sekkis from the next toji and extend them -----------------
+        let mut sekkis = self.sekkis[NEXT_TOJI_INDEX..].to_vec();
+        extend_sekkis_to_toji(&mut sekkis)?;
+
+        // 2. Keep sakus from the one before the next toji and extend them ------
+        let jd_toji = sekkis.first().expect("Should have 24 elements").0;
+        let jd_last_toji = sekkis.last().expect("Should have 24 elements").0;
+        let first = saku_before(&self.sakus, jd_toji);
+        let mut sakus = self.sakus[first..].to_vec();
+        let jd_last_saku = *sakus.last().expect("Should have saku after the last toji");
+
+        // Sakus are 29.3 to 29.8 days apart, so the day after the last one excludes it
+        let next_sakus =
+            calculate_sakus(jd_last_saku + 1.0, jd_last_toji + 2.0 * SYNODIC_MONTH + 1.0)?;
+        sakus.extend(next_sakus);
+        let last = saku_after(&sakus, jd_last_toji) + 1;
+        sakus.truncate(last + 1);
+
+        Ok(YearMonths::name(sekkis, sakus, calendar))
+    }
+
+    /// Names the months from the sekkis and sakus.
+    fn name(sekkis: Vec<(f64, Sekki)>, sakus: Vec<f64>, calendar: &TempoCalendar) -> YearMonths {
+        let chukis: Vec<_> = sekkis.iter().filter(|x| x.1.is_chuki()).copied().collect();
+        let month_days: Vec<_> = sakus.iter().map(|s| calendar.month_start_day(*s)).collect();
+        let months = calendar.name_months(&month_days, &chukis);
+        YearMonths {
+            sekkis,
+            sakus,
+            months,
+        }
+    }
+
+    /// Gets the instant of the toji.
+    fn jd_toji(&self) -> f64 {
+        self.sekkis.first().expect("Should have 24 elements").0
+    }
+}
+
+/// Calculates 24-sekkis forward to the toji after the next toji, where the first one is a toji.
+fn extend_sekkis_to_toji(sekkis: &mut Vec<(f64, Sekki)>) -> Result<()> {
+    let mut last_sekki = *sekkis.last().expect("Should be have at 1 element");
+    while sekkis.len() <= 2 * NEXT_TOJI_INDEX {
+        // Sekkis are 14.7 to 15.8 days apart
+        let next_sekki = calculate_leading_24sekki(last_sekki.0 + 16.0)?;
+        sekkis.push(next_sekki);
+        last_sekki = next_sekki;
+    }
+    Ok(())
+}
+
+/// Iterates 24-sekkis backward from the leading one of the instant.
+/// The iteration ends at the first error.
+fn preceding_24sekkis(jd_now: f64) -> impl Iterator<Item = Result<(f64, Sekki)>> {
+    // Any instant between two sekkis leads to the former
+    successors(Some(calculate_leading_24sekki(jd_now)), |s| {
+        s.as_ref()
+            .ok()
+            .map(|s| calculate_leading_24sekki(s.0 - 1.0))
+    })
+}
+
+/// Finds the index of the last saku at or before the instant in the sorted sakus.
+fn saku_before(sakus: &[f64], jd: f64) -> usize {
+    sakus
+        .partition_point(|s| *s <= jd)
+        .checked_sub(1)
+        .expect("Should have saku before the instant")
+}
+
+/// Finds the index of the first saku at or after the instant in the sorted sakus.
+fn saku_after(sakus: &[f64], jd: f64) -> usize {
+    let index = sakus.partition_point(|s| *s < jd);
+    assert!(index < sakus.len(), "Should have saku after the instant");
+    index
+}
+
+/// Gets cached tempo months from the last toji, if any.
+fn cached_tempo_months(jd: f64) -> Result<Option<Vec<TempoMonth>>> {
+    let date = date_of_julian_day(local_day(jd, TEMPO.meridian_offset))?;
+    let cache = YEAR_MONTHS
+        .read()
+        .expect("Year months should not be poisoned");
+
+    // Tojis are around December 22nd
+    if date.month() == 12 {
+        match cache.get(&(date.year() + 1)) {
+            Some(year_months) if jd >= year_months.jd_toji() => {
+                return Ok(Some(year_months.months.clone()))
+            }
+            Some(_) => (),
+            None => return Ok(None),
+        }
+    }
+    Ok(cache.get(&date.year()).map(|y| y.months.clone()))
+}
+
+/// Calculates the months between the 11th months around the date, which name them.
+/// Only sakus and chukis around the date are calculated.
+fn calculate_month_window(jd: f64, calendar: &TempoCalendar) -> Result<Vec<TempoMonth>> {
+    // Day boundaries shift dates by less than a day
+    let sakus = calculate_sakus(jd - SUI_SPAN - 1.0, jd + SUI_SPAN + 1.0)?;
+    let month_days: Vec<_> = sakus.iter().map(|s| calendar.month_start_day(*s)).collect();
+
+    let chukis = calendar.chukis(sakus[0] - 1.0, sakus[sakus.len() - 1] + 1.0)?;
+    let mut tempo_months = calendar.name_months(&month_days, &chukis);
+    tempo_months.retain(|m| m.month != 0);
+    Ok(tempo_months)
+}
+
+/// Gets tempo months from the toji in the previous year to the toji in the next year.
+/// If the previous year is cached, its months are extended instead.
+fn cached_year_months(year: i32) -> Result<Arc<YearMonths>> {
+    let cached = |year: i32| {
+        YEAR_MONTHS
+            .read()
+            .expect("Year months should not be poisoned")
+            .get(&year)
+            .cloned()
+    };
+    if let Some(cached) = cached(year) {
+        YEAR_MONTHS_COUNTERS.record(true);
+        return Ok(cached);
     }
 
-    (jd, l_sun0)
+    // Later requests wait for the first one and share its result
+    let flight = YEAR_MONTHS_IN_FLIGHT
+        .lock()
+        .expect("Year months in flight should not be poisoned")
+        .entry(year)
+        .or_default()
+        .clone();
+    let _guard = flight
+        .lock()
+        .expect("Year months in flight should not be poisoned");
+    let cached_in_flight = cached(year);
+    YEAR_MONTHS_COUNTERS.record(cached_in_flight.is_some());
+    if let Some(cached) = cached_in_flight {
+        return Ok(cached);
+    }
+
+    let calculated = match cached(year - 1) {
+        Some(previous) => previous.calculate_next(&TEMPO),
+        None => {
+            let jd_mid_year = julian_day_of(NaiveDate::from_ymd(year, 6, 1));
+            YearMonths::calculate(jd_mid_year, &TEMPO)
+        }
+    }
+    .map(Arc::new);
+    if let Ok(calculated) = &calculated {
+        let evicted = YEAR_MONTHS
+            .write()
+            .expect("Year months should not be poisoned")
+            .insert(year, calculated.clone());
+        YEAR_MONTHS_COUNTERS.record_evictions(evicted);
+    }
+    YEAR_MONTHS_IN_FLIGHT
+        .lock()
+        .expect("Year months in flight should not be poisoned")
+        .remove(&year);
+    calculated
+}
+
+/// Gets the counters of the year months cache.
+pub fn year_months_cache_stats() -> CacheStats {
+    let entries = YEAR_MONTHS
+        .read()
+        .expect("Year months should not be poisoned")
+        .len();
+    YEAR_MONTHS_COUNTERS.stats(entries)
+}
+
+/// Calculates true chukis (定気) whose instants are in the range.
+pub fn calculate_true_chukis(jd_from: f64, jd_to: f64) -> Result<Vec<(f64, Sekki)>> {
+    let mut chukis = vec![];
+    for sekki in preceding_24sekkis(jd_to) {
+        let sekki = sekki?;
+        if sekki.0 < jd_from {
+            break;
+        }
+        if sekki.1.is_chuki() {
+            chukis.push(sekki);
+        }
+    }
+    chukis.reverse();
+    Ok(chukis)
+}
+
+/// Calculates mean chukis (平気) whose instants are in the range.
+/// Each year from a true toji to the next is divided into 12 equal terms.
+pub fn calculate_mean_chukis(jd_from: f64, jd_to: f64) -> Result<Vec<(f64, Sekki)>> {
+    // The toji found from the mean motion may be after the start, then the previous one is taken
+    let toji_longitude = Sekki::Toji.longitude();
+    let delta_l = Degree(sun_longitude(jd_from)).ahead_of(toji_longitude);
+    let mut jd_toji = sun_reaches(toji_longitude, jd_from - delta_l.0 * 365.2 / 360.0)?;
+    if jd_toji > jd_from {
+        jd_toji = sun_reaches(toji_longitude, jd_toji - 365.2)?;
+    }
+
+    let mut chukis = vec![];
+    while jd_toji < jd_to {
+        let jd_toji_next = sun_reaches(toji_longitude, jd_toji + 365.2)?;
+        let term = (jd_toji_next - jd_toji) / 12.0;
+        for index in 0..12 {
+            let jd = jd_toji + index as f64 * term;
+            let sekki = Sekki::from_number((Sekki::Toji.to_number() + index * 2) % 24)
+                .expect("Should be rounded by 24");
+            if (jd_from..jd_to).contains(&jd) {
+                chukis.push((jd, sekki));
+            }
+        }
+        jd_toji = jd_toji_next;
+    }
+    Ok(chukis)
+}
+
+/// Calculates leading 24-sekki with Julian Date.
+/// The precomputed table is used if it covers the instant.
+pub fn calculate_leading_24sekki(jd_now: f64) -> Result<(f64, Sekki)> {
+    if let Some(sekki) = event_table::leading_24sekki(jd_now) {
+        return Ok(sekki);
+    }
+    let (root, sekki) = diagnose_leading_24sekki(jd_now)?;
+    Ok((root.root, sekki))
+}
+
+/// Calculates leading 24-sekki with the convergence.
+/// The residual is the difference of the sun longitude in degree.
+pub fn diagnose_leading_24sekki(jd_now: f64) -> Result<(RootDiagnostics, Sekki)> {
+    budget::check()?;
+    let sekki = Sekki::from_longitude(Degree(sun_longitude(jd_now)));
+
+    // The sun moves at least 0.95 degree a day, so it was behind the sekki 16 days ago
+    let root = diagnose_root_within(jd_now - 16.0, jd_now, default_tolerance(), |jd| {
+        Degree(sun_longitude(jd)).signed_from(sekki.longitude()).0
+    })
+    .map_err(|_| ConversionError::SolverDidNotConverge { jd: jd_now })?;
+    Ok((root, sekki))
 }
 
 /// Calculates saku chuki with Julian Date.
 pub fn calculate_leading_saku(jd_now: f64) -> Result<f64> {
-    let mut delta_t = 1.0f64;
-    let mut jd = jd_now;
-    let mut iter_count = 0;
-    while delta_t.abs() > (1.0 / 86400.0) {
-        let l_sun = sun_longitude(jd);
-        let l_moon = moon_longitude(jd);
-        let mut delta_l = l_moon - l_sun;
+    calculate_leading_saku_with(jd_now, &Jcg78)
+}
 
-        if iter_count == 0 && delta_l < 0.0 {
-            delta_l = delta_l.rem_euclid(360.0);
-        }
+/// Calculates saku chuki in the model with Julian Date.
+pub fn calculate_leading_saku_with<M: LongitudeModel + ?Sized>(
+    jd_now: f64,
+    model: &M,
+) -> Result<f64> {
+    budget::check()?;
+    let elongation = |jd: f64| elongation_with(jd, model).0;
 
-        if (0.0..20.0).contains(&l_sun) && l_moon >= 300.0 {
-            delta_l = 360.0 - delta_l.rem_euclid(360.0);
+    // True saku deviates from the mean motion by less than a day
+    let jd_mean = jd_now - elongation_with(jd_now, model).normalized().0 * SYNODIC_MONTH / 360.0;
+    let (jd_low, jd_high) = (jd_mean - 3.0, (jd_mean + 3.0).min(jd_now));
+    // Leading sakus of dates close to each other are the same, so the cached approximation pays
+    let root = match model.approximation() {
+        Some(approximation) => {
+            let approximate_elongation = |jd: f64| elongation_with(jd, approximation).0;
+            diagnose_root_refined(
+                jd_low,
+                jd_high,
+                model.tolerance(),
+                approximate_elongation,
+                elongation,
+            )
+        }
+        None => diagnose_root_within(jd_low, jd_high, model.tolerance(), elongation),
+    };
+    match root {
+        Ok(saku) => Ok(saku.root),
+        Err(_) => {
+            // The bracket from the mean motion can miss the saku, so the month before is scanned
+            let sakus = diagnose_sakus_with(jd_now - SYNODIC_MONTH - 2.0, jd_now, model)?;
+            match sakus.last() {
+                Some(saku) => Ok(saku.root),
+                None => Err(ConversionError::SolverDidNotConverge { jd: jd_now }.into()),
+            }
         }
+    }
+}
 
-        if delta_l.abs() > 40.0 {
-            delta_l = delta_l.rem_euclid(360.0);
+/// Calculates the elongation of the moon from the sun in the model, normalized into (-180, 180].
+/// Sakus are the roots of it.
+fn elongation_with<M: LongitudeModel + ?Sized>(jd: f64, model: &M) -> Degree {
+    Degree(model.moon_longitude(jd)).signed_from(Degree(model.sun_longitude(jd)))
+}
+
+/// Enumerates sakus whose instants are in the range, with Julian Date.
+/// The precomputed table is used if it covers the range.
+pub fn calculate_sakus(jd_from: f64, jd_to: f64) -> Result<Vec<f64>> {
+    if let Some(sakus) = event_table::sakus(jd_from, jd_to) {
+        return Ok(sakus);
+    }
+    calculate_sakus_with(jd_from, jd_to, &Jcg78)
+}
+
+/// Enumerates sakus whose instants are in the range in the model, with Julian Date.
+pub fn calculate_sakus_with<M: LongitudeModel + ?Sized>(
+    jd_from: f64,
+    jd_to: f64,
+    model: &M,
+) -> Result<Vec<f64>> {
+    let roots = diagnose_sakus_with(jd_from, jd_to, model)?;
+    Ok(roots.into_iter().map(|r| r.root).collect())
+}
+
+/// Enumerates sakus whose instants are in the range in the model, with the convergence of each.
+/// The residuals are the elongations in degree.
+pub fn diagnose_sakus_with<M: LongitudeModel + ?Sized>(
+    jd_from: f64,
+    jd_to: f64,
+    model: &M,
+) -> Result<Vec<RootDiagnostics>> {
+    // The elongation increases by 10 to 16 degree a day, so steps of 2 days never skip sakus
+    let step = 2.0;
+    let elongation = |jd: f64| elongation_with(jd, model).0;
+
+    // The scanning grid is evaluated at once
+    let grid: Vec<_> = successors(Some(jd_from), |&jd| {
+        (jd < jd_to).then(|| (jd + step).min(jd_to))
+    })
+    .collect();
+    let elongations: Vec<_> = model
+        .sun_longitudes(&grid)
+        .into_iter()
+        .zip(model.moon_longitudes(&grid))
+        .map(|(l_sun, l_moon)| Degree(l_moon).signed_from(Degree(l_sun)).0)
+        .collect();
+
+    let mut sakus = vec![];
+    for (jds, values) in grid.windows(2).zip(elongations.windows(2)) {
+        let (jd, jd_next) = (jds[0], jds[1]);
+        let (last, next) = (values[0], values[1]);
+        // Excludes jumps at bochi
+        if last < 0.0 && next >= 0.0 && next - last < 90.0 {
+            budget::check()?;
+            let saku = diagnose_saku_within(jd, jd_next, model.tolerance(), elongation)?;
+            if saku.root < jd_to {
+                sakus.push(saku);
+            }
         }
+    }
+    Ok(sakus)
+}
 
-        delta_t = delta_l * 29.530589 / 360.0;
-        jd -= delta_t;
+/// Finds the saku in the bracket, retrying on each half of it if the solver fails on the whole.
+fn diagnose_saku_within(
+    low: f64,
+    high: f64,
+    tolerance: f64,
+    elongation: impl Fn(f64) -> f64,
+) -> Result<RootDiagnostics> {
+    let middle = 0.5 * (low + high);
+    diagnose_root_within(low, high, tolerance, &elongation)
+        .or_else(|_| diagnose_root_within(low, middle, tolerance, &elongation))
+        .or_else(|_| diagnose_root_within(middle, high, tolerance, &elongation))
+        .map_err(|_| ConversionError::SolverDidNotConverge { jd: middle }.into())
+}
+
+/// Calculates the nearest bochi (望, full moon) with Julian Date.
+pub fn calculate_nearest_bochi(jd_now: f64) -> Result<f64> {
+    let mut jd = jd_now;
+    for _ in 0..MAX_BOCHI_ITERATIONS {
+        let delta_l = Degree(moon_longitude(jd) - sun_longitude(jd)).signed_from(Degree(180.0));
 
-        if iter_count >= 30 {
-            bail!("Saku calculation cannot be finished");
-        } else if iter_count == 15 {
-            jd = jd_now - 26.0;
+        let delta_t = delta_l.0 * 29.530589 / 360.0;
+        jd -= delta_t;
+        if delta_t.abs() <= (1.0 / 86400.0) {
+            return Ok(jd);
         }
-        iter_count += 1;
     }
 
-    Ok(jd)
+    Err(ConversionError::SolverDidNotConverge { jd: jd_now }.into())
 }
@@ -0,0 +1,204 @@
+//! Contains validation of computed calendars against reference data such as NAOJ koyomi tables.
+//!
+//! The reference is a CSV file with a header row. The `date` column (`YYYY-MM-DD` in JST) is
+//! required, and each of `kyureki` (`YYYY/MM/DD`, the leap month prefixed by `L` or `閏`),
+//! `sekki` (the name in kanji, empty if none falls on the date) and `rokuyo` (in kanji)
+//! is compared only if present. Fields are not quoted.
+
+use std::{fs, path::Path};
+
+use anyhow::{bail, Context, Result};
+use chrono::prelude::*;
+
+use crate::{
+    sekki::Sekki,
+    tempo::{RokuyoScheme, TempoDate},
+};
+
+/// Represents a row of the reference data.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReferenceRow {
+    /// Line number in the file, for reports.
+    pub line: usize,
+
+    /// JST date of the row.
+    pub date: NaiveDate,
+
+    /// Kyureki date, if the column is present.
+    pub kyureki: Option<String>,
+
+    /// Name of the sekki on the date, empty if none, if the column is present.
+    pub sekki: Option<String>,
+
+    /// Name of the rokuyo, if the column is present.
+    pub rokuyo: Option<String>,
+}
+
+/// Represents a field which differs from the reference.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Mismatch {
+    /// Line number in the reference file.
+    pub line: usize,
+
+    /// JST date of the row.
+    pub date: NaiveDate,
+
+    /// Name of the column.
+    pub field: &'static str,
+
+    /// Value in the reference.
+    pub expected: String,
+
+    /// Computed value.
+    pub computed: String,
+}
+
+/// Represents the result of a validation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationReport {
+    /// Number of the validated rows.
+    pub checked: usize,
+
+    /// Fields which differ from the reference.
+    pub mismatches: Vec<Mismatch>,
+}
+
+/// Loads the reference data from the CSV file.
+pub fn load_reference(path: &Path) -> Result<Vec<ReferenceRow>> {
+    let content = fs::read_to_string(path)
+        .with_context(|| format!("Failed to read reference data {}", path.display()))?;
+    parse_reference(&content)
+}
+
+/// Parses the reference data in CSV.
+fn parse_reference(content: &str) -> Result<Vec<ReferenceRow>> {
+    let mut lines = content
+        .lines()
+        .enumerate()
+        .map(|(i, l)| (i + 1, l.trim()))
+        .filter(|(_, l)| !l.is_empty());
+    let header: Vec<_> = match lines.next() {
+        Some((_, header)) => header.split(',').map(str::trim).collect(),
+        None => bail!("Reference data has no header"),
+    };
+    let column = |name| header.iter().position(|h| *h == name);
+    let date_column = match column("date") {
+        Some(index) => index,
+        None => bail!("Reference data has no date column"),
+    };
+    let (kyureki_column, sekki_column, rokuyo_column) =
+        (column("kyureki"), column("sekki"), column("rokuyo"));
+
+    lines
+        .map(|(line, l)| {
+            let fields: Vec<_> = l.split(',').map(str::trim).collect();
+            if fields.len() != header.len() {
+                bail!(
+                    "Line {} has {} fields, not {}",
+                    line,
+                    fields.len(),
+                    header.len()
+                );
+            }
+            let date = NaiveDate::parse_from_str(fields[date_column], "%Y-%m-%d")
+                .with_context(|| format!("Line {} has invalid date", line))?;
+            let field = |index: Option<usize>| index.map(|i| fields[i].to_string());
+            Ok(ReferenceRow {
+                line,
+                date,
+                kyureki: field(kyureki_column),
+                sekki: field(sekki_column),
+                rokuyo: field(rokuyo_column),
+            })
+        })
+        .collect()
+}
+
+/// Compares the computed calendar with the reference rows.
+pub fn validate(rows: &[ReferenceRow]) -> Result<ValidationReport> {
+    let mut mismatches = vec![];
+    for row in rows {
+        let mut compare = |field, expected: &str, computed: String| {
+            if expected != computed {
+                mismatches.push(Mismatch {
+                    line: row.line,
+                    date: row.date,
+                    field,
+                    expected: expected.to_string(),
+                    computed,
+                });
+            }
+        };
+
+        let tempo_date = TempoDate::from_gregory_date(Utc.from_utc_date(&row.date))?;
+        if let Some(expected) = &row.kyureki {
+            // Written in the same form as computed, so that only the values are compared
+            let normalized = normalize_kyureki(expected).unwrap_or_else(|| expected.clone());
+            compare("kyureki", &normalized, tempo_date.to_string());
+        }
+        if let Some(expected) = &row.sekki {
+            let computed = Sekki::on_jst_date(row.date)?.map_or("", |(s, _)| s.to_japanese());
+            compare("sekki", expected, computed.to_string());
+        }
+        if let Some(expected) = &row.rokuyo {
+            let computed = tempo_date.rokuyo(RokuyoScheme::Standard).to_japanese();
+            compare("rokuyo", expected, computed.to_string());
+        }
+    }
+
+    Ok(ValidationReport {
+        checked: rows.len(),
+        mismatches,
+    })
+}
+
+/// Rewrites the kyureki date like `TempoDate`, or `None` if not a date.
+fn normalize_kyureki(kyureki: &str) -> Option<String> {
+    let mut parts = kyureki.split('/');
+    let (year, month, day) = (parts.next()?, parts.next()?, parts.next()?);
+    if parts.next().is_some() {
+        return None;
+    }
+
+    let (leap_month, month) = match month.strip_prefix('L').or_else(|| month.strip_prefix('閏')) {
+        Some(month) => (true, month),
+        None => (false, month),
+    };
+    let tempo_date = TempoDate {
+        year: year.parse().ok()?,
+        leap_month,
+        month: month.parse().ok()?,
+        day: day.parse().ok()?,
+        ..Default::default()
+    };
+    Some(tempo_date.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Validates the reference data, which is never generated by this crate.
+    fn assert_validated(content: &str, rows: usize) {
+        let report = validate(&parse_reference(content).unwrap()).unwrap();
+        assert_eq!(report.checked, rows);
+        assert!(
+            report.mismatches.is_empty(),
+            "Mismatches: {:?}",
+            report.mismatches
+        );
+    }
+
+    #[test]
+    fn close_chukis_are_validated() {
+        // Cross-checked with qreki.awk, which decides months by the JST dates of chukis
+        assert_validated(include_str!("../data/close_chukis.csv"), 106);
+    }
+
+    #[test]
+    fn double_chukis_are_validated() {
+        // 2033 follows the leap 11th month recommended by 日本カレンダー暦文化振興協会,
+        // and 2052 is cross-checked with qreki.awk
+        assert_validated(include_str!("../data/double_chukis.csv"), 32);
+    }
+}
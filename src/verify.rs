@@ -0,0 +1,154 @@
+//! Contains verification of round trips between Gregorian dates and tempo calendar.
+//!
+//! Conversions are expected to satisfy `from_gregory_date(d)?.to_gregory_date()? == d`
+//! for every date in the supported range, so that they can be used in both directions.
+
+use std::iter::successors;
+
+use anyhow::Result;
+use chrono::prelude::*;
+
+use crate::{
+    astro::longitude::jcg78::ensure_supported_date, error::ConversionError, tempo::TempoDate,
+};
+
+/// Represents a date which does not come back by the round trip.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundTripViolation {
+    /// The Gregorian date converted first.
+    pub date: NaiveDate,
+
+    /// The converted date in tempo calendar, if converted.
+    pub tempo_date: Option<TempoDate>,
+
+    /// The Gregorian date converted back, if converted.
+    pub round_trip: Option<NaiveDate>,
+
+    /// The error which stopped the round trip, if any.
+    pub error: Option<String>,
+}
+
+/// Represents the result of the verification over a range.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RoundTripReport {
+    /// Number of the checked dates.
+    pub checked: usize,
+
+    /// Dates which violate the round trip.
+    pub violations: Vec<RoundTripViolation>,
+}
+
+/// Verifies the round trip of every date in the range, including both ends.
+pub fn verify_round_trip(first: NaiveDate, last: NaiveDate) -> Result<RoundTripReport> {
+    ensure_supported_date(first)?;
+    ensure_supported_date(last)?;
+    if first > last {
+        return Err(ConversionError::ReversedRange {
+            from: first,
+            to: last,
+        }
+        .into());
+    }
+
+    let mut report = RoundTripReport {
+        checked: 0,
+        violations: vec![],
+    };
+    for date in successors(Some(first), |d| d.succ_opt()).take_while(|d| *d <= last) {
+        report.checked += 1;
+        let violation = |tempo_date, round_trip, error| RoundTripViolation {
+            date,
+            tempo_date,
+            round_trip,
+            error,
+        };
+
+        let tempo_date = match TempoDate::from_gregory_date(Utc.from_utc_date(&date)) {
+            Ok(tempo_date) => tempo_date,
+            Err(e) => {
+                report
+                    .violations
+                    .push(violation(None, None, Some(e.to_string())));
+                continue;
+            }
+        };
+        match tempo_date.to_gregory_date() {
+            Ok(round_trip) if round_trip == date => (),
+            Ok(round_trip) => {
+                report
+                    .violations
+                    .push(violation(Some(tempo_date), Some(round_trip), None))
+            }
+            Err(e) => {
+                report
+                    .violations
+                    .push(violation(Some(tempo_date), None, Some(e.to_string())))
+            }
+        }
+    }
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{gyoji::lunar_new_year, tempo::leap_month_of_year};
+
+    #[test]
+    fn round_trip_holds_from_1900_to_2100() {
+        let report = verify_round_trip(
+            NaiveDate::from_ymd(1900, 1, 1),
+            NaiveDate::from_ymd(2100, 12, 31),
+        )
+        .unwrap();
+        assert_eq!(report.checked, 73414);
+        assert!(
+            report.violations.is_empty(),
+            "Violations: {:?}",
+            report.violations
+        );
+    }
+
+    #[test]
+    fn published_leap_months_and_new_years_hold() {
+        // Round trips hold even if months are misnamed, so names are checked with published ones.
+        // 2033 follows the leap 11th month recommended by 日本カレンダー暦文化振興協会.
+        let leap_months = [
+            (1984, Some(10)),
+            (1985, None),
+            (2001, Some(4)),
+            (2012, Some(3)),
+            (2014, Some(9)),
+            (2015, None),
+            (2017, Some(5)),
+            (2020, Some(4)),
+            (2023, Some(2)),
+            (2025, Some(6)),
+            (2033, Some(11)),
+            (2034, None),
+        ];
+        for (year, leap_month) in leap_months {
+            assert_eq!(leap_month_of_year(year).unwrap(), leap_month, "{}", year);
+        }
+
+        let new_years = [
+            (1985, 2, 20),
+            (2001, 1, 24),
+            (2012, 1, 23),
+            (2014, 1, 31),
+            (2015, 2, 19),
+            (2017, 1, 28),
+            (2020, 1, 25),
+            (2023, 1, 22),
+            (2025, 1, 29),
+            (2034, 2, 19),
+        ];
+        for (year, month, day) in new_years {
+            let expected = NaiveDate::from_ymd(year as i32, month, day);
+            assert_eq!(lunar_new_year(year).unwrap(), expected, "{}", year);
+        }
+
+        let tempo_date = TempoDate::from_gregory_date(Utc.ymd(2014, 11, 22)).unwrap();
+        assert_eq!(tempo_date.to_string(), "2014/10/01");
+    }
+}
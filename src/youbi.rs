@@ -0,0 +1,58 @@
+//! Contains youbi (曜日), days of the week.
+
+use chrono::prelude::*;
+
+/// Represents youbi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Youbi(pub Weekday);
+
+impl Youbi {
+    /// Gets the youbi of the date.
+    pub fn of_day(date: NaiveDate) -> Youbi {
+        Youbi(date.weekday())
+    }
+
+    /// Gets Japanese string in single kanji.
+    pub fn to_japanese(self) -> &'static str {
+        match self.0 {
+            Weekday::Sun => "日",
+            Weekday::Mon => "月",
+            Weekday::Tue => "火",
+            Weekday::Wed => "水",
+            Weekday::Thu => "木",
+            Weekday::Fri => "金",
+            Weekday::Sat => "土",
+        }
+    }
+
+    /// Gets Hepburn romanization.
+    pub fn to_romaji(self) -> &'static str {
+        match self.0 {
+            Weekday::Sun => "Nichiyōbi",
+            Weekday::Mon => "Getsuyōbi",
+            Weekday::Tue => "Kayōbi",
+            Weekday::Wed => "Suiyōbi",
+            Weekday::Thu => "Mokuyōbi",
+            Weekday::Fri => "Kin'yōbi",
+            Weekday::Sat => "Doyōbi",
+        }
+    }
+
+    /// Gets English string.
+    pub fn to_english(self) -> &'static str {
+        match self.0 {
+            Weekday::Sun => "Sunday",
+            Weekday::Mon => "Monday",
+            Weekday::Tue => "Tuesday",
+            Weekday::Wed => "Wednesday",
+            Weekday::Thu => "Thursday",
+            Weekday::Fri => "Friday",
+            Weekday::Sat => "Saturday",
+        }
+    }
+
+    /// Gets ISO 8601 weekday number, from 1 (Monday) to 7 (Sunday).
+    pub fn to_iso_number(self) -> u32 {
+        self.0.number_from_monday()
+    }
+}
@@ -0,0 +1,141 @@
+//! Contains zassetsu (雑節) calculations.
+
+use std::iter::successors;
+
+use anyhow::Result;
+use chrono::prelude::*;
+
+use crate::{
+    astro::{
+        angle::Degree,
+        events::sun_reaches,
+        julian::{from_julian_date, to_julian_date},
+        longitude::jcg78::ensure_supported_year,
+    },
+    calendar::JST_OFFSET,
+    kanshi::Junishi,
+    sekki::Sekki,
+};
+
+/// Sun longitude at which summer doyo starts.
+const SUMMER_DOYO_LONGITUDE: Degree = Degree(117.0);
+
+/// Represents zassetsu.
+#[allow(dead_code)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Zassetsu {
+    Setsubun,
+    Higan,
+    Shanichi,
+    Hachijuhachiya,
+    Nyubai,
+    Hangesho,
+    Doyo,
+    Nihyakutoka,
+    Nihyakuhatsuka,
+}
+
+#[allow(dead_code)]
+impl Zassetsu {
+    /// Gets Japanese string.
+    pub fn to_japanese(self) -> &'static str {
+        match self {
+            Zassetsu::Setsubun => "節分",
+            Zassetsu::Higan => "彼岸",
+            Zassetsu::Shanichi => "社日",
+            Zassetsu::Hachijuhachiya => "八十八夜",
+            Zassetsu::Nyubai => "入梅",
+            Zassetsu::Hangesho => "半夏生",
+            Zassetsu::Doyo => "土用",
+            Zassetsu::Nihyakutoka => "二百十日",
+            Zassetsu::Nihyakuhatsuka => "二百二十日",
+        }
+    }
+
+    /// Gets Hepburn romanization.
+    pub fn to_romaji(self) -> &'static str {
+        match self {
+            Zassetsu::Setsubun => "Setsubun",
+            Zassetsu::Higan => "Higan",
+            Zassetsu::Shanichi => "Shanichi",
+            Zassetsu::Hachijuhachiya => "Hachijūhachiya",
+            Zassetsu::Nyubai => "Nyūbai",
+            Zassetsu::Hangesho => "Hangeshō",
+            Zassetsu::Doyo => "Doyō",
+            Zassetsu::Nihyakutoka => "Nihyakutōka",
+            Zassetsu::Nihyakuhatsuka => "Nihyakuhatsuka",
+        }
+    }
+
+    /// Gets English translation.
+    pub fn to_english(self) -> &'static str {
+        match self {
+            Zassetsu::Setsubun => "eve of spring",
+            Zassetsu::Higan => "equinoctial week",
+            Zassetsu::Shanichi => "day of the earth god",
+            Zassetsu::Hachijuhachiya => "eighty-eighth night",
+            Zassetsu::Nyubai => "start of the rainy season",
+            Zassetsu::Hangesho => "crow-dipper sprouting",
+            Zassetsu::Doyo => "seasonal transition",
+            Zassetsu::Nihyakutoka => "two hundred and tenth day",
+            Zassetsu::Nihyakuhatsuka => "two hundred and twentieth day",
+        }
+    }
+
+    /// Gets the sun longitude which defines this zassetsu, if any.
+    pub fn longitude(self) -> Option<Degree> {
+        match self {
+            Zassetsu::Nyubai => Some(Degree(80.0)),
+            Zassetsu::Hangesho => Some(Degree(100.0)),
+            _ => None,
+        }
+    }
+
+    /// Calculates the JST date of this zassetsu in the year, if it is defined by the sun longitude.
+    pub fn jst_date_in_year(self, year: i32) -> Result<Option<NaiveDate>> {
+        ensure_supported_year(year)?;
+        self.longitude()
+            .map(|longitude| {
+                // Shunbun is around 3/20, the 79th day of the year
+                let jd_year = to_julian_date(&Utc.ymd(year, 1, 1).and_hms(0, 0, 0));
+                let jd_guess = jd_year + 79.0 + longitude.0 * 365.2 / 360.0;
+                jst_date_of(sun_reaches(longitude, jd_guess)?)
+            })
+            .transpose()
+    }
+}
+
+/// Represents a doyo period, both ends inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Doyo {
+    pub start: NaiveDate,
+    pub end: NaiveDate,
+}
+
+impl Doyo {
+    /// Calculates the summer doyo period of the year.
+    pub fn summer(year: i32) -> Result<Doyo> {
+        ensure_supported_year(year)?;
+        let jd_guess = to_julian_date(&Utc.ymd(year, 7, 20).and_hms(0, 0, 0));
+        let jd_start = sun_reaches(SUMMER_DOYO_LONGITUDE, jd_guess)?;
+        let jd_risshu = sun_reaches(Sekki::Risshu.longitude(), jd_start + 18.0)?;
+
+        Ok(Doyo {
+            start: jst_date_of(jd_start)?,
+            end: jst_date_of(jd_risshu)?.pred(),
+        })
+    }
+
+    /// Enumerates the days of the ox (丑の日) in this period.
+    pub fn ushi_days(&self) -> Vec<NaiveDate> {
+        successors(Some(self.start), |d| d.succ_opt())
+            .take_while(|d| *d <= self.end)
+            .filter(|d| Junishi::of_day(*d) == Junishi::Ushi)
+            .collect()
+    }
+}
+
+/// Gets the JST date of the instant.
+fn jst_date_of(jd: f64) -> Result<NaiveDate> {
+    Ok(from_julian_date(jd + JST_OFFSET)?.date().naive_utc())
+}